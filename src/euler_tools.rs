@@ -1,6 +1,18 @@
 pub mod additional_number_constants;
+pub mod arbitrary_precision;
 pub mod collection_tools;
+pub mod combinatorics;
+pub mod continued_fraction;
+pub mod digit_signature;
+pub mod farey;
 pub mod figurate_numbers;
+pub mod gcd;
+pub mod linear_recurrence;
+pub mod matrix;
+pub mod modular;
+pub mod palindromes;
+pub mod partitions;
+pub mod pell;
 pub mod prime_finder;
 pub mod ratio;
 
@@ -10,8 +22,9 @@ use std::{
 };
 
 use additional_number_constants::MorePositiveConstants;
+use matrix::Matrix;
 use num_bigint::BigUint;
-use num_traits::{CheckedAdd, CheckedMul, ConstOne, Num, One, PrimInt, Zero};
+use num_traits::{CheckedAdd, CheckedMul, ConstOne, Num, One, PrimInt, ToPrimitive, Zero};
 use prime_finder::CoprimePairsIterator;
 
 pub struct Fibonacci<I: Clone + Zero + One + CheckedAdd> {
@@ -36,6 +49,21 @@ pub fn fibonacci_iterator<I: Clone + Zero + One + CheckedAdd>() -> Fibonacci<I>
     }
 }
 
+/// Finds the nth Fibonacci number, counting from F(0) = 0, in O(log n) steps. This also computes
+/// F(n + 1), so primitive types overflow one term early.
+#[allow(dead_code)]
+pub fn fibonacci<I: Clone + Zero + One>(n: u64) -> I {
+    let step = Matrix::from_rows(vec![vec![I::one(), I::one()], vec![I::one(), I::zero()]]);
+    step.pow(n)[(0, 1)].clone()
+}
+
+/// Finds the nth Fibonacci number mod modulus in O(log n) steps
+#[allow(dead_code)]
+pub fn fibonacci_mod(n: u64, modulus: u64) -> u64 {
+    let step = Matrix::from_rows(vec![vec![1, 1], vec![1, 0]]);
+    step.pow_mod(n, modulus)[(0, 1)]
+}
+
 pub struct BoundedIterator<T>
 where
     T: std::cmp::PartialOrd<T>,
@@ -103,7 +131,7 @@ where
     }
 }
 
-impl<I> DigitIterator<I>
+impl<I: DigitSource> DigitIterator<I>
 where
     DigitIterator<I>: Iterator,
 {
@@ -111,16 +139,62 @@ where
         DigitIterator {
             remaining_number: number,
             radix,
+            buffered_digits: I::Buffer::default(),
+        }
+    }
+
+    /// Finds the rest of the digits up front so they can be taken from either end, with
+    /// next_back giving the most significant digit first
+    #[allow(dead_code)]
+    pub fn double_ended(self) -> DoubleEndedDigitIterator<<Self as Iterator>::Item> {
+        DoubleEndedDigitIterator {
+            digits: self.collect::<Vec<_>>().into_iter(),
         }
     }
 }
-pub struct DigitIterator<I> {
+/// Whatever a DigitIterator needs to keep besides the number and the radix. Primitives don't need
+/// anything, so their iterators stay as small as the numbers themselves.
+pub trait DigitSource {
+    type Buffer: Default;
+}
+
+pub struct DigitIterator<I: DigitSource> {
     remaining_number: I,
     radix: I,
+    buffered_digits: I::Buffer,
+}
+
+/// The digits of a number from least to most significant, which can also be reversed to go from
+/// most to least significant
+pub struct DoubleEndedDigitIterator<D> {
+    digits: std::vec::IntoIter<D>,
 }
 
+impl<D> Iterator for DoubleEndedDigitIterator<D> {
+    type Item = D;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.digits.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.digits.size_hint()
+    }
+}
+
+impl<D> DoubleEndedIterator for DoubleEndedDigitIterator<D> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.digits.next_back()
+    }
+}
+
+impl<D> ExactSizeIterator for DoubleEndedDigitIterator<D> {}
+
 macro_rules! digit_iterator_impl {
     ($($prim_type:ty),*) => { $(
+        impl DigitSource for $prim_type {
+            type Buffer = ();
+        }
         impl Iterator for DigitIterator<$prim_type>{
             type Item = $prim_type;
 
@@ -141,28 +215,44 @@ macro_rules! digit_iterator_impl {
             }
             #[allow(dead_code)]
             pub fn combine_digits(digits: &[u8]) -> $prim_type{
+                DigitIterator::<$prim_type>::combine_digits_radix(digits, 10)
+            }
+
+            /// The inverse of new_radix, taking the least significant digit first
+            #[allow(dead_code)]
+            pub fn combine_digits_radix(digits: &[u8], radix: $prim_type) -> $prim_type{
                 let mut num:$prim_type = 0;
                 for digit in digits.iter().rev(){
-                    num = 10 * num + (*digit as $prim_type);
+                    num = radix * num + (*digit as $prim_type);
                 }
                 num
             }
 
             #[allow(dead_code)]
             pub fn combine_digits_big_endian(digits:&[$prim_type]) ->$prim_type {
+                DigitIterator::<$prim_type>::combine_digits_big_endian_radix(digits, 10)
+            }
+
+            #[allow(dead_code)]
+            pub fn combine_digits_big_endian_radix(digits:&[$prim_type], radix: $prim_type) ->$prim_type {
                 let mut num = 0;
                 for digit in digits.iter(){
-                    num = 10 * num + digit;
+                    num = radix * num + digit;
                 }
                 num
             }
 
             #[allow(dead_code)]
             pub fn combine_digits_rotated(digits: &[$prim_type], starting_index: usize) -> $prim_type {
+                DigitIterator::<$prim_type>::combine_digits_rotated_radix(digits, starting_index, 10)
+            }
+
+            #[allow(dead_code)]
+            pub fn combine_digits_rotated_radix(digits: &[$prim_type], starting_index: usize, radix: $prim_type) -> $prim_type {
                 let mut num = 0;
                 for i in (starting_index..starting_index+ digits.len() ).rev(){
                     let digit = digits[i % digits.len()];
-                    num = 10 * num + digit;
+                    num = radix * num + digit;
                 }
                 num
             }
@@ -193,6 +283,64 @@ macro_rules! digit_iterator_impl {
 digit_iterator_impl!(u8, u16, u32, u64, u128, usize);
 digit_iterator_impl!(i8, i16, i32, i64, i128, isize);
 
+/// Digits already split off the number, most significant first so the next one can be popped off
+/// the end
+impl DigitSource for BigUint {
+    type Buffer = Vec<u8>;
+}
+
+/// BigUint digits come out as u32s, so the radix can't be bigger than 2^32
+impl Iterator for DigitIterator<BigUint> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffered_digits.is_empty() && !self.remaining_number.is_zero() {
+            match self.radix.to_u32() {
+                // split off every digit at once, rather than dividing the whole number per digit
+                Some(radix) if radix <= 256 => {
+                    self.buffered_digits =
+                        std::mem::take(&mut self.remaining_number).to_radix_be(radix);
+                }
+                _ => {
+                    let digit = &self.remaining_number % &self.radix;
+                    self.remaining_number /= &self.radix;
+                    return Some(digit.to_u32().expect("the radix should fit in a u32"));
+                }
+            }
+        }
+        self.buffered_digits.pop().map(u32::from)
+    }
+}
+
+#[allow(dead_code)]
+impl DigitIterator<BigUint> {
+    pub fn new(number: BigUint) -> Self {
+        DigitIterator::new_radix(number, BigUint::from(10u32))
+    }
+
+    pub fn combine_digits(digits: &[u32]) -> BigUint {
+        DigitIterator::<BigUint>::combine_digits_radix(digits, 10)
+    }
+
+    /// The inverse of new_radix, taking the least significant digit first
+    pub fn combine_digits_radix(digits: &[u32], radix: u32) -> BigUint {
+        digits
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |num, digit| num * radix + digit)
+    }
+
+    pub fn combine_digits_big_endian(digits: &[u32]) -> BigUint {
+        DigitIterator::<BigUint>::combine_digits_big_endian_radix(digits, 10)
+    }
+
+    pub fn combine_digits_big_endian_radix(digits: &[u32], radix: u32) -> BigUint {
+        digits
+            .iter()
+            .fold(BigUint::zero(), |num, digit| num * radix + digit)
+    }
+}
+
 pub trait RotateDigits: Sized {
     fn rotate_digits(n: Self, len: u32) -> Self;
     #[allow(dead_code)]
@@ -278,6 +426,7 @@ macro_rules! is_pandigital_impl {
 is_pandigital_impl!(u8, u16, u32, u64, u128, usize);
 is_pandigital_impl!(i8, i16, i32, i64, i128, isize);
 
+#[allow(dead_code)]
 pub fn is_bin_palindrome(n: usize) -> bool {
     let mut constructor = n;
     let mut rev_n = 0;
@@ -288,6 +437,7 @@ pub fn is_bin_palindrome(n: usize) -> bool {
     rev_n == n
 }
 
+#[allow(dead_code)]
 pub fn is_palindrome(string: &str) -> bool {
     let num_bytes = string.bytes().len();
     let mut reverse_string = string.bytes().rev();
@@ -490,8 +640,9 @@ mod tests {
     use num_traits::Pow;
     use std::ops::Add;
 
-    use super::{fibonacci_iterator, Fibonacci};
+    use super::{fibonacci, fibonacci_iterator, fibonacci_mod, Fibonacci};
     use crate::euler_tools::is_bin_palindrome;
+    use crate::euler_tools::modular::ModInt;
     #[allow(unused_imports)]
     use crate::euler_tools::{
         additional_number_constants::MorePositiveConstants, big_factorial, factorial, IsPandigital,
@@ -507,6 +658,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fast_fib() {
+        let stepped: Vec<u64> = fibonacci_iterator().take(93).collect();
+        for (n, fib) in stepped.iter().enumerate() {
+            assert_eq!(fibonacci::<u64>(n as u64), *fib);
+            assert_eq!(fibonacci_mod(n as u64, 1_000_007), fib % 1_000_007);
+        }
+        assert_eq!(fibonacci::<BigUint>(1000).to_string().len(), 209);
+        assert_eq!(
+            fibonacci::<ModInt<1_000_000_007>>(1_000_000_000_000_000).value(),
+            fibonacci_mod(1_000_000_000_000_000, 1_000_000_007)
+        );
+        assert_eq!(fibonacci_mod(10, 1), 0);
+    }
+
     #[test]
     fn test_bounded_it() {
         const BOUND: i32 = 5;
@@ -539,6 +705,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_digits_radix() {
+        assert_eq!(
+            super::DigitIterator::<u32>::new_radix(0b1101, 2).collect::<Vec<u32>>(),
+            vec![1, 0, 1, 1]
+        );
+        assert_eq!(
+            super::DigitIterator::<u32>::combine_digits_radix(&[1, 0, 1, 1], 2),
+            13
+        );
+        assert_eq!(
+            super::DigitIterator::<u64>::combine_digits_big_endian_radix(&[15, 0, 1], 16),
+            0xf01
+        );
+        assert_eq!(
+            super::DigitIterator::<u64>::combine_digits_rotated_radix(&[1, 2, 3], 1, 8),
+            0o132
+        );
+        assert_eq!(super::DigitIterator::<u64>::combine_digits(&[3, 2, 1]), 123);
+        assert_eq!(
+            super::DigitIterator::<u64>::combine_digits_rotated(&[1, 2, 3], 1),
+            132
+        );
+        for radix in 2..=16u64 {
+            let digits: Vec<u64> = super::DigitIterator::<u64>::new_radix(987654321, radix)
+                .double_ended()
+                .rev()
+                .collect();
+            assert_eq!(
+                super::DigitIterator::<u64>::combine_digits_big_endian_radix(&digits, radix),
+                987654321
+            );
+        }
+    }
+
+    #[test]
+    fn test_double_ended_digits() {
+        let mut digits = super::DigitIterator::<i64>::new(12045).double_ended();
+        assert_eq!(digits.len(), 5);
+        assert_eq!(digits.next_back(), Some(1));
+        assert_eq!(digits.next(), Some(5));
+        assert_eq!(digits.next_back(), Some(2));
+        assert_eq!(digits.collect::<Vec<i64>>(), vec![4, 0]);
+        assert_eq!(
+            super::DigitIterator::<u8>::new(0)
+                .double_ended()
+                .next_back(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_big_digits() {
+        let number = BigUint::from(2u32).pow(100u32);
+        let digits: Vec<u32> = super::DigitIterator::<BigUint>::new(number.clone()).collect();
+        let expected: Vec<u32> = number
+            .to_string()
+            .bytes()
+            .rev()
+            .map(|b| (b - b'0') as u32)
+            .collect();
+        assert_eq!(digits, expected);
+        assert_eq!(
+            super::DigitIterator::<BigUint>::combine_digits(&digits),
+            number
+        );
+        let most_significant_first: Vec<u32> = super::DigitIterator::<BigUint>::new(number.clone())
+            .double_ended()
+            .rev()
+            .collect();
+        assert_eq!(
+            super::DigitIterator::<BigUint>::combine_digits_big_endian(&most_significant_first),
+            number
+        );
+
+        let radix = 1 << 20;
+        let digits: Vec<u32> =
+            super::DigitIterator::<BigUint>::new_radix(number.clone(), BigUint::from(radix))
+                .collect();
+        assert_eq!(digits, vec![0, 0, 0, 0, 0, 1]);
+        assert_eq!(
+            super::DigitIterator::<BigUint>::combine_digits_radix(&digits, radix),
+            number
+        );
+        assert_eq!(
+            super::DigitIterator::<BigUint>::new(BigUint::from(0u32)).count(),
+            0
+        );
+
+        // only BigUint iterators carry a digit buffer
+        assert_eq!(
+            std::mem::size_of::<super::DigitIterator<u64>>(),
+            2 * std::mem::size_of::<u64>()
+        );
+    }
+
     #[test]
     fn small_lam_w() {
         // values computed using https://www.had2know.org/academics/lambert-w-function-calculator.html
@@ -0,0 +1,220 @@
+use bigdecimal::BigDecimal;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Pow, Zero};
+
+/// floor(sqrt(n)) by Newton's method
+pub fn big_isqrt(n: &BigUint) -> BigUint {
+    big_nth_root(n, 2)
+}
+
+/// floor(n^(1/k)) by Newton's method
+pub fn big_nth_root(n: &BigUint, k: u32) -> BigUint {
+    assert_ne!(k, 0, "the 0th root is undefined");
+    if k == 1 || n.is_zero() {
+        return n.clone();
+    }
+
+    // start from a power of 2 above the root, so that every step moves down towards it
+    let mut root = BigUint::one() << n.bits().div_ceil(k as u64);
+    loop {
+        // x -> ((k - 1) x + n / x^(k-1)) / k
+        let next = ((k - 1) * &root + n / Pow::pow(&root, k - 1)) / k;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// sqrt(n) rounded down to the given number of decimal places
+#[allow(dead_code)]
+pub fn big_decimal_sqrt(n: &BigUint, decimal_places: u32) -> BigDecimal {
+    let scaled = n * BigUint::from(10u8).pow(2 * decimal_places);
+    BigDecimal::new(BigInt::from(big_isqrt(&scaled)), decimal_places as i64)
+}
+
+/// Splits n into digits in the given radix, least significant first, with at least one digit
+fn radix_digits(n: &BigUint, radix: u32) -> Vec<u32> {
+    assert!(
+        (2..=256).contains(&radix),
+        "radix must be between 2 and 256"
+    );
+    n.to_radix_le(radix)
+        .into_iter()
+        .map(|digit| digit as u32)
+        .collect()
+}
+
+/// The digits of sqrt(n), most significant first, computed one at a time by the schoolbook
+/// digit-by-digit method. The integer digits come first, and the fractional digits never end.
+pub struct SqrtDigits {
+    radix: u32,
+    /// pairs of digits of n that haven't been brought down yet, least significant first
+    groups: Vec<u32>,
+    integer_digit_count: usize,
+    remainder: BigUint,
+    root: BigUint,
+}
+
+impl SqrtDigits {
+    /// The number of digits before the radix point
+    #[allow(dead_code)]
+    pub fn integer_digit_count(&self) -> usize {
+        self.integer_digit_count
+    }
+}
+
+impl Iterator for SqrtDigits {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let radix = self.radix;
+        let current = &self.remainder * radix * radix + self.groups.pop().unwrap_or(0);
+
+        // the largest digit with (2 radix root + digit) digit <= current
+        let doubled_root = &self.root * 2u8 * radix;
+        let cost = |digit: u32| (&doubled_root + digit) * digit;
+        let (mut low, mut high) = (0, radix - 1);
+        while low < high {
+            let middle = (low + high).div_ceil(2);
+            if cost(middle) <= current {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+
+        self.remainder = current - cost(low);
+        self.root = &self.root * radix + low;
+        Some(low)
+    }
+}
+
+pub fn sqrt_digits(n: &BigUint, radix: u32) -> SqrtDigits {
+    let digits = radix_digits(n, radix);
+    let groups: Vec<u32> = digits
+        .chunks(2)
+        .map(|pair| pair[0] + pair.get(1).unwrap_or(&0) * radix)
+        .collect();
+    SqrtDigits {
+        radix,
+        integer_digit_count: groups.len(),
+        groups,
+        remainder: BigUint::zero(),
+        root: BigUint::zero(),
+    }
+}
+
+/// The digits of numerator / denominator, most significant first, by long division. The integer
+/// digits come first, and the fractional digits never end, even if they're all 0.
+pub struct RationalDigits {
+    radix: u32,
+    /// integer digits that haven't been returned yet, least significant first
+    integer_digits: Vec<u32>,
+    integer_digit_count: usize,
+    remainder: BigUint,
+    denominator: BigUint,
+}
+
+impl RationalDigits {
+    /// The number of digits before the radix point
+    #[allow(dead_code)]
+    pub fn integer_digit_count(&self) -> usize {
+        self.integer_digit_count
+    }
+}
+
+impl Iterator for RationalDigits {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(digit) = self.integer_digits.pop() {
+            return Some(digit);
+        }
+        let scaled = &self.remainder * self.radix;
+        let digit = &scaled / &self.denominator;
+        self.remainder = scaled - &digit * &self.denominator;
+        Some(digit.iter_u32_digits().next().unwrap_or(0))
+    }
+}
+
+#[allow(dead_code)]
+pub fn rational_digits(numerator: &BigUint, denominator: &BigUint, radix: u32) -> RationalDigits {
+    assert!(!denominator.is_zero(), "denominator must be nonzero");
+    let integer_digits = radix_digits(&(numerator / denominator), radix);
+    RationalDigits {
+        radix,
+        integer_digit_count: integer_digits.len(),
+        integer_digits,
+        remainder: numerator % denominator,
+        denominator: denominator.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use num_bigint::BigUint;
+
+    use super::{big_decimal_sqrt, big_isqrt, big_nth_root, rational_digits, sqrt_digits};
+
+    #[test]
+    fn roots_match_num_bigint() {
+        for n in (0..2000u32).chain([u32::MAX]) {
+            let n = BigUint::from(n);
+            assert_eq!(big_isqrt(&n), n.sqrt(), "sqrt({n})");
+            for k in 1..6 {
+                assert_eq!(big_nth_root(&n, k), n.nth_root(k), "root {k} of {n}");
+            }
+        }
+
+        let big = BigUint::from(3u8).pow(1001);
+        assert_eq!(big_isqrt(&big), big.sqrt());
+        assert_eq!(big_nth_root(&big, 7), big.nth_root(7));
+        assert_eq!(big_nth_root(&(&big * &big * &big), 3), big);
+    }
+
+    #[test]
+    fn sqrt_digit_expansions() {
+        let digits = sqrt_digits(&BigUint::from(2u8), 10);
+        assert_eq!(digits.integer_digit_count(), 1);
+        let digits: Vec<u32> = digits.take(12).collect();
+        assert_eq!(digits, [1, 4, 1, 4, 2, 1, 3, 5, 6, 2, 3, 7]);
+
+        // the first 100 digits of sqrt(2) add up to 475
+        let sum: u32 = sqrt_digits(&BigUint::from(2u8), 10).take(100).sum();
+        assert_eq!(sum, 475);
+
+        let digits = sqrt_digits(&BigUint::from(15241u32), 10);
+        assert_eq!(digits.integer_digit_count(), 3);
+        let digits: Vec<u32> = digits.take(5).collect();
+        assert_eq!(digits, [1, 2, 3, 4, 5]);
+
+        // sqrt(2) = 1.0110101000001... in binary
+        let digits: Vec<u32> = sqrt_digits(&BigUint::from(2u8), 2).take(13).collect();
+        assert_eq!(digits, [1, 0, 1, 1, 0, 1, 0, 1, 0, 0, 0, 0, 0]);
+
+        let decimal = big_decimal_sqrt(&BigUint::from(2u8), 10);
+        assert_eq!(decimal, BigDecimal::from_str("1.4142135623").unwrap());
+    }
+
+    #[test]
+    fn rational_digit_expansions() {
+        let digits = rational_digits(&BigUint::from(22u8), &BigUint::from(7u8), 10);
+        assert_eq!(digits.integer_digit_count(), 1);
+        let digits: Vec<u32> = digits.take(8).collect();
+        assert_eq!(digits, [3, 1, 4, 2, 8, 5, 7, 1]);
+
+        let digits: Vec<u32> = rational_digits(&BigUint::from(1u8), &BigUint::from(4u8), 10)
+            .take(4)
+            .collect();
+        assert_eq!(digits, [0, 2, 5, 0]);
+
+        let digits: Vec<u32> = rational_digits(&BigUint::from(1000u32), &BigUint::from(3u8), 16)
+            .take(5)
+            .collect();
+        assert_eq!(digits, [1, 4, 13, 5, 5]);
+    }
+}
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 
+#[allow(dead_code)]
 pub trait CheckedGet<T> {
     fn checked_get<I: TryInto<usize>>(&self, index: I) -> Option<&T>;
 }
@@ -0,0 +1,362 @@
+use num_bigint::BigUint;
+use num_traits::{One, PrimInt, Zero};
+
+use crate::euler_tools::{
+    gcd::gcd,
+    modular::{mod_inverse, mod_mul, mod_pow},
+};
+
+/// n choose k, or None if it doesn't fit in I. Intermediate results never get bigger than the
+/// answer, so this only overflows when it has to.
+pub fn binomial<I: PrimInt>(n: I, k: I) -> Option<I> {
+    if k < I::zero() || k > n {
+        return Some(I::zero());
+    }
+    let k = k.min(n - k);
+
+    // C(n, i+1) = C(n, i) (n - i) / (i + 1), dividing out the shared factors first
+    let mut result = I::one();
+    let mut i = I::zero();
+    while i < k {
+        let divisor = i + I::one();
+        let shared = gcd(result, divisor);
+        result = (result / shared).checked_mul(&((n - i) / (divisor / shared)))?;
+        i = i + I::one();
+    }
+    Some(result)
+}
+
+/// n choose k for arbitrarily large results
+pub fn big_binomial(n: u64, k: u64) -> BigUint {
+    if k > n {
+        return BigUint::zero();
+    }
+    let k = k.min(n - k);
+    (0..k).fold(BigUint::one(), |result, i| result * (n - i) / (i + 1))
+}
+
+/// Iterates over the rows of Pascal's triangle, starting from [1]
+pub struct PascalRows<T> {
+    row: Vec<T>,
+}
+
+impl<T: Clone + Zero + One> Iterator for PascalRows<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut next = Vec::with_capacity(self.row.len() + 1);
+        next.push(T::one());
+        next.extend(
+            self.row
+                .windows(2)
+                .map(|pair| pair[0].clone() + pair[1].clone()),
+        );
+        next.push(T::one());
+        Some(std::mem::replace(&mut self.row, next))
+    }
+}
+
+#[allow(dead_code)]
+pub fn pascal_rows<T: Clone + Zero + One>() -> PascalRows<T> {
+    PascalRows {
+        row: vec![T::one()],
+    }
+}
+
+/// Rows 0 through max_n of Pascal's triangle, so that table[n][k] = C(n, k)
+#[allow(dead_code)]
+pub fn pascal_table<T: Clone + Zero + One>(max_n: usize) -> Vec<Vec<T>> {
+    pascal_rows().take(max_n + 1).collect()
+}
+
+/// n choose k mod p for small n and k, where p is prime
+fn small_binomial_mod_prime(n: u64, k: u64, p: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    let (numerator, denominator) = (0..k).fold((1, 1), |(numerator, denominator), i| {
+        (mod_mul(numerator, n - i, p), mod_mul(denominator, i + 1, p))
+    });
+    mod_mul(
+        numerator,
+        mod_inverse(denominator, p).expect("k < p, so k! is coprime to p"),
+        p,
+    )
+}
+
+/// n choose k mod a prime p by Lucas' theorem, which multiplies together the binomials of the
+/// base p digits of n and k. Each digit costs O(p) at worst.
+#[allow(dead_code)]
+pub fn binomial_mod_prime(n: u64, k: u64, p: u64) -> u64 {
+    let (mut n, mut k) = (n, k);
+    let mut result = 1 % p;
+    while k > 0 && result != 0 {
+        result = mod_mul(result, small_binomial_mod_prime(n % p, k % p, p), p);
+        n /= p;
+        k /= p;
+    }
+    result
+}
+
+/// n choose k mod p^e by Granville's generalization of Lucas' theorem. This builds a table of
+/// p^e entries, so it's meant for prime powers up to about 10^7.
+#[allow(dead_code)]
+pub fn binomial_mod_prime_power(n: u64, k: u64, p: u64, e: u32) -> u64 {
+    let modulus = p.pow(e);
+    if k > n {
+        return 0;
+    }
+
+    // By Kummer's theorem, the power of p in C(n, k) is the number of carries when adding k and
+    // n - k in base p
+    let p_power =
+        legendre_valuation(n, p) - legendre_valuation(k, p) - legendre_valuation(n - k, p);
+    if p_power >= e as u64 {
+        return 0;
+    }
+
+    // unit_products[i] is the product of every j in 1..=i that p doesn't divide, mod p^e
+    let mut unit_products = Vec::with_capacity(modulus as usize + 1);
+    unit_products.push(1 % modulus);
+    for j in 1..=modulus {
+        let previous = unit_products[j as usize - 1];
+        unit_products.push(if j % p == 0 {
+            previous
+        } else {
+            mod_mul(previous, j, modulus)
+        });
+    }
+
+    let unit_factorial = |m: u64| p_free_factorial(m, p, modulus, &unit_products);
+    let denominator = mod_mul(unit_factorial(k), unit_factorial(n - k), modulus);
+    let unit_part = mod_mul(
+        unit_factorial(n),
+        mod_inverse(denominator, modulus).expect("every factor of p was removed"),
+        modulus,
+    );
+    mod_mul(unit_part, mod_pow(p, p_power, modulus), modulus)
+}
+
+/// The power of p in m!, which is m/p + m/p^2 + ...
+fn legendre_valuation(m: u64, p: u64) -> u64 {
+    let mut valuation = 0;
+    let mut m = m;
+    while m > 0 {
+        m /= p;
+        valuation += m;
+    }
+    valuation
+}
+
+/// m! with every factor of p removed, mod p^e. The unit products repeat every p^e, and the
+/// multiples of p contribute (m/p)! with its factors of p removed in turn.
+fn p_free_factorial(m: u64, p: u64, modulus: u64, unit_products: &[u64]) -> u64 {
+    let mut result = 1 % modulus;
+    let mut m = m;
+    while m > 0 {
+        let full_cycles = mod_pow(unit_products[modulus as usize], m / modulus, modulus);
+        let partial_cycle = unit_products[(m % modulus) as usize];
+        result = mod_mul(
+            result,
+            mod_mul(full_cycles, partial_cycle, modulus),
+            modulus,
+        );
+        m /= p;
+    }
+    result
+}
+
+/// The Catalan numbers C_0, ..., C_limit, which count balanced bracket sequences, binary trees,
+/// monotone lattice paths below the diagonal and much more
+#[allow(dead_code)]
+pub fn catalan_numbers(limit: usize) -> Vec<BigUint> {
+    let mut catalan = Vec::with_capacity(limit + 1);
+    catalan.push(BigUint::one());
+    for n in 0..limit as u64 {
+        // C_{n+1} = C_n 2 (2n + 1) / (n + 2)
+        let next = &catalan[n as usize] * (2 * (2 * n + 1)) / (n + 2);
+        catalan.push(next);
+    }
+    catalan
+}
+
+/// The nth Catalan number, C(2n, n) / (n + 1)
+#[allow(dead_code)]
+pub fn catalan(n: u64) -> BigUint {
+    big_binomial(2 * n, n) / (n + 1)
+}
+
+/// Unsigned Stirling numbers of the first kind, with table[n][k] counting the permutations of n
+/// elements with k cycles
+#[allow(dead_code)]
+pub fn stirling_first_table<T: Clone + Zero + One + From<u64>>(max_n: usize) -> Vec<Vec<T>> {
+    // c(n+1, k) = n c(n, k) + c(n, k-1)
+    stirling_table(max_n, |n, _| T::from(n as u64))
+}
+
+/// Stirling numbers of the second kind, with table[n][k] counting the ways to split n elements
+/// into k nonempty sets
+#[allow(dead_code)]
+pub fn stirling_second_table<T: Clone + Zero + One + From<u64>>(max_n: usize) -> Vec<Vec<T>> {
+    // S(n+1, k) = k S(n, k) + S(n, k-1)
+    stirling_table(max_n, |_, k| T::from(k as u64))
+}
+
+/// Fills in a triangle with table[n+1][k] = weight(n, k) table[n][k] + table[n][k-1]
+fn stirling_table<T, F>(max_n: usize, weight: F) -> Vec<Vec<T>>
+where
+    T: Clone + Zero + One,
+    F: Fn(usize, usize) -> T,
+{
+    let mut table: Vec<Vec<T>> = Vec::with_capacity(max_n + 1);
+    table.push(vec![T::one()]);
+    for n in 0..max_n {
+        let previous = &table[n];
+        let row = (0..=n + 1)
+            .map(|k| {
+                let stay = previous
+                    .get(k)
+                    .map_or(T::zero(), |entry| weight(n, k) * entry.clone());
+                let join = k.checked_sub(1).map_or(T::zero(), |k| previous[k].clone());
+                stay + join
+            })
+            .collect();
+        table.push(row);
+    }
+    table
+}
+
+/// The Bell numbers B_0, ..., B_limit, which count the ways to split a set into nonempty parts.
+/// These come from the Bell triangle, which only needs additions.
+#[allow(dead_code)]
+pub fn bell_numbers<T: Clone + Zero + One>(limit: usize) -> Vec<T> {
+    let mut bell = vec![T::one()];
+    let mut row = vec![T::one()];
+    for _ in 0..limit {
+        // each row starts with the end of the previous one
+        let mut next = Vec::with_capacity(row.len() + 1);
+        next.push(row.last().expect("rows are never empty").clone());
+        for entry in &row {
+            let sum = next.last().expect("rows are never empty").clone() + entry.clone();
+            next.push(sum);
+        }
+        row = next;
+        bell.push(row[0].clone());
+    }
+    bell
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{
+        bell_numbers, big_binomial, binomial, binomial_mod_prime, binomial_mod_prime_power,
+        catalan, catalan_numbers, pascal_rows, pascal_table, stirling_first_table,
+        stirling_second_table,
+    };
+    use crate::euler_tools::modular::ModInt;
+
+    #[test]
+    fn binomials() {
+        let table: Vec<Vec<u64>> = pascal_table(60);
+        for (n, row) in table.iter().enumerate() {
+            for (k, entry) in row.iter().enumerate() {
+                assert_eq!(binomial(n as u64, k as u64), Some(*entry), "C({n}, {k})");
+                assert_eq!(big_binomial(n as u64, k as u64), BigUint::from(*entry));
+            }
+            assert_eq!(binomial(n as u64, n as u64 + 1), Some(0));
+        }
+        assert_eq!(binomial(5i32, -1), Some(0));
+
+        // C(67, 33) is the last middle binomial to fit in a u64, and C(68, 34) doesn't
+        assert_eq!(binomial(66u64, 33), Some(7_219_428_434_016_265_740));
+        assert_eq!(binomial(67u64, 33), Some(14_226_520_737_620_288_370));
+        assert_eq!(binomial(68u64, 34), None);
+        assert_eq!(binomial(40u8, 39), Some(40));
+        assert_eq!(
+            big_binomial(100, 50).to_string(),
+            "100891344545564193334812497256"
+        );
+
+        let big_rows: Vec<Vec<BigUint>> = pascal_rows().take(101).collect();
+        assert_eq!(big_rows[100][50], big_binomial(100, 50));
+    }
+
+    #[test]
+    fn modular_binomials() {
+        for p in [2u64, 3, 5, 7, 13] {
+            let table: Vec<Vec<BigUint>> = pascal_table(200);
+            for (n, row) in table.iter().enumerate() {
+                for (k, entry) in row.iter().enumerate() {
+                    let expected = entry % p;
+                    let found = binomial_mod_prime(n as u64, k as u64, p);
+                    assert_eq!(BigUint::from(found), expected, "C({n}, {k}) mod {p}");
+                }
+            }
+        }
+
+        for (p, e) in [(2u64, 1), (2, 5), (3, 3), (5, 2), (7, 2), (11, 1)] {
+            let modulus = p.pow(e);
+            let table: Vec<Vec<BigUint>> = pascal_table(150);
+            for (n, row) in table.iter().enumerate() {
+                for (k, entry) in row.iter().enumerate() {
+                    let expected = entry % modulus;
+                    let found = binomial_mod_prime_power(n as u64, k as u64, p, e);
+                    assert_eq!(BigUint::from(found), expected, "C({n}, {k}) mod {p}^{e}");
+                }
+            }
+        }
+
+        // Lucas' theorem handles huge n with a big prime. The base p digits are small enough to
+        // check against exact binomials.
+        let p = 1_000_000_007;
+        let (n, k) = (15 * p * p + 10 * p + 30, 7 * p * p + 4 * p + 12);
+        let expected = [(15, 7), (10, 4), (30, 12)]
+            .into_iter()
+            .map(|(n, k)| big_binomial(n, k) % p)
+            .product::<BigUint>()
+            % p;
+        assert_eq!(BigUint::from(binomial_mod_prime(n, k, p)), expected);
+        // a digit of k bigger than the matching digit of n makes p divide the binomial
+        assert_eq!(binomial_mod_prime(n, 7 * p * p + 11 * p + 12, p), 0);
+    }
+
+    #[test]
+    fn catalans() {
+        let expected = [1u32, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796];
+        assert_eq!(catalan_numbers(10), expected.map(BigUint::from));
+        for (n, expected) in catalan_numbers(60).iter().enumerate() {
+            assert_eq!(catalan(n as u64), *expected);
+        }
+    }
+
+    #[test]
+    fn stirling_and_bell() {
+        let first: Vec<Vec<u64>> = stirling_first_table(6);
+        assert_eq!(first[4], [0, 6, 11, 6, 1]);
+        assert_eq!(first[6], [0, 120, 274, 225, 85, 15, 1]);
+
+        let second: Vec<Vec<u64>> = stirling_second_table(6);
+        assert_eq!(second[4], [0, 1, 7, 6, 1]);
+        assert_eq!(second[6], [0, 1, 31, 90, 65, 15, 1]);
+
+        // each row of the first kind adds up to n!, and the second kind adds up to the Bell numbers
+        let bell: Vec<u64> = bell_numbers(20);
+        assert_eq!(bell[..8], [1, 1, 2, 5, 15, 52, 203, 877]);
+        let first: Vec<Vec<u64>> = stirling_first_table(20);
+        let second: Vec<Vec<u64>> = stirling_second_table(20);
+        let mut factorial = 1;
+        for n in 0..=20 {
+            if n > 0 {
+                factorial *= n as u64;
+            }
+            assert_eq!(first[n].iter().sum::<u64>(), factorial);
+            assert_eq!(second[n].iter().sum::<u64>(), bell[n]);
+        }
+
+        let modular: Vec<ModInt<1_000_000_007>> = bell_numbers(1000);
+        assert_eq!(modular[2].value(), 2);
+    }
+}
@@ -0,0 +1,366 @@
+use std::cmp::Ordering;
+
+use integer_sqrt::IntegerSquareRoot;
+use num_bigint::BigUint;
+
+use crate::euler_tools::ratio::{Ratio, RatioInteger, floor_div_rem};
+
+/// A continued fraction [a0; a1, a2, ...] whose terms repeat after the integer part, like the
+/// expansion of any quadratic irrational sqrt(n)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodicContinuedFraction {
+    pub integer_part: u64,
+    /// empty when the expansion is finite
+    pub period: Vec<u64>,
+}
+
+impl PeriodicContinuedFraction {
+    /// Every term, starting with the integer part. This is infinite unless the period is empty.
+    pub fn terms(&self) -> impl Iterator<Item = u64> + '_ {
+        std::iter::once(self.integer_part).chain(self.period.iter().copied().cycle())
+    }
+}
+
+/// Expands sqrt(n) as [a0; (a1, ..., a_l)]. The period always ends with 2 a0, and is empty when n
+/// is a perfect square.
+pub fn sqrt_expansion(n: u64) -> PeriodicContinuedFraction {
+    let root = n.integer_sqrt() as u128;
+    let n = n as u128;
+    let mut period = Vec::new();
+    if root * root != n {
+        // sqrt(n) = a + (sqrt(n) - m) / denominator at each step
+        let (mut m, mut denominator, mut a) = (0, 1, root);
+        while a != 2 * root {
+            m = denominator * a - m;
+            denominator = (n - m * m) / denominator;
+            a = (root + m) / denominator;
+            period.push(a as u64);
+        }
+    }
+    PeriodicContinuedFraction {
+        integer_part: root as u64,
+        period,
+    }
+}
+
+/// Expands a ratio by the Euclidean algorithm. Only the first term can be negative, and the last
+/// term is never 1 unless it's the only term.
+#[allow(dead_code)]
+pub fn ratio_expansion<N: RatioInteger>(ratio: Ratio<N>) -> Vec<N> {
+    let (mut numerator, mut denominator) = ratio.into_parts();
+
+    let mut terms = Vec::new();
+    while !denominator.is_zero() {
+        // floored division, so every remainder is nonnegative
+        let (term, remainder) = floor_div_rem(numerator, denominator.clone());
+        terms.push(term);
+        (numerator, denominator) = (denominator, remainder);
+    }
+    terms
+}
+
+/// Builds a continued fraction from some leading terms followed by a repeating pattern, where the
+/// kth block (starting from k = 1) can depend on k
+pub fn pattern_terms<'a, F, B>(prefix: &'a [u64], block: F) -> impl Iterator<Item = u64> + 'a
+where
+    F: FnMut(u64) -> B + 'a,
+    B: IntoIterator<Item = u64> + 'a,
+{
+    prefix.iter().copied().chain((1..).flat_map(block))
+}
+
+/// e = [2; 1, 2, 1, 1, 4, 1, 1, 6, 1, ...]
+pub fn e_terms() -> impl Iterator<Item = u64> {
+    pattern_terms(&[2], |k| [1, 2 * k, 1])
+}
+
+/// Iterates over the convergents p/q of a continued fraction. The iterator ends with the terms, or
+/// when p or q no longer fits in T.
+pub struct Convergents<T, I> {
+    terms: I,
+    previous: (T, T),
+    current: (T, T),
+    /// set once a convergent overflows or the terms run out, so later calls can't skip a term
+    finished: bool,
+}
+
+impl<T, I> Iterator for Convergents<T, I>
+where
+    T: RatioInteger + TryFrom<u64>,
+    I: Iterator<Item = u64>,
+{
+    type Item = Ratio<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let Some(next) = self.next_convergent() else {
+            self.finished = true;
+            return None;
+        };
+        let (p, q) = next.clone();
+        self.previous = std::mem::replace(&mut self.current, next);
+        Some(Ratio::new(p, q))
+    }
+}
+
+impl<T, I> Convergents<T, I>
+where
+    T: RatioInteger + TryFrom<u64>,
+    I: Iterator<Item = u64>,
+{
+    fn next_convergent(&mut self) -> Option<(T, T)> {
+        // p_k = a_k p_{k-1} + p_{k-2}, and the same for q
+        let term = T::try_from(self.terms.next()?).ok()?;
+        let step = |current: &T, previous: &T| term.checked_mul(current)?.checked_add(previous);
+        Some((
+            step(&self.current.0, &self.previous.0)?,
+            step(&self.current.1, &self.previous.1)?,
+        ))
+    }
+}
+
+pub fn convergents<T, I>(terms: I) -> Convergents<T, I::IntoIter>
+where
+    T: RatioInteger + TryFrom<u64>,
+    I: IntoIterator<Item = u64>,
+{
+    Convergents {
+        terms: terms.into_iter(),
+        previous: (T::zero(), T::one()),
+        current: (T::one(), T::zero()),
+        finished: false,
+    }
+}
+
+/// Finds the closest fraction to some x with a denominator of at most max_denominator, given the
+/// terms of x and a way to compare x to a fraction. Ties go to the smaller denominator. The
+/// numerator is about x max_denominator, which needn't fit in a u64.
+fn best_approximation<I, F>(terms: I, max_denominator: u64, compare_to: F) -> Ratio<u128>
+where
+    I: IntoIterator<Item = u64>,
+    F: Fn(&BigUint, &BigUint) -> Ordering,
+{
+    assert_ne!(max_denominator, 0, "max_denominator must be positive");
+    let max_denominator = max_denominator as u128;
+    let mut terms = terms.into_iter();
+    let (mut p_prev, mut q_prev) = (1u128, 0u128);
+    let (mut p, mut q) = (
+        terms.next().expect("there's always an integer part") as u128,
+        1,
+    );
+
+    for term in terms {
+        let term = term as u128;
+        if term * q + q_prev > max_denominator {
+            // The largest semiconvergent that fits lands on the other side of x from p/q, so
+            // whichever is closer depends on which side of their midpoint x is on
+            let j = (max_denominator - q_prev) / q;
+            if j == 0 {
+                break;
+            }
+            let (semi_p, semi_q) = (j * p + p_prev, j * q + q_prev);
+            let midpoint = compare_to(
+                &(BigUint::from(p) * semi_q + BigUint::from(semi_p) * q),
+                &(BigUint::from(q) * semi_q * 2u8),
+            );
+            let convergent_is_below = BigUint::from(p) * semi_q < BigUint::from(semi_p) * q;
+            let semiconvergent_is_closer = if convergent_is_below {
+                midpoint == Ordering::Greater
+            } else {
+                midpoint == Ordering::Less
+            };
+            if semiconvergent_is_closer {
+                return Ratio::new(semi_p, semi_q);
+            }
+            break;
+        }
+        (p_prev, p) = (p, term * p + p_prev);
+        (q_prev, q) = (q, term * q + q_prev);
+    }
+    Ratio::new(p, q)
+}
+
+/// Finds the closest fraction to numerator / denominator whose denominator is at most
+/// max_denominator
+#[allow(dead_code)]
+pub fn best_rational_approximation(
+    numerator: u64,
+    denominator: u64,
+    max_denominator: u64,
+) -> Ratio<u64> {
+    let terms = ratio_expansion(Ratio::new(numerator, denominator));
+    let (big_numerator, big_denominator) = (BigUint::from(numerator), BigUint::from(denominator));
+    let (p, q) = best_approximation(terms, max_denominator, |p, q| {
+        (&big_numerator * q).cmp(&(p * &big_denominator))
+    })
+    .into_parts();
+    // either p/q is the ratio itself, or q < denominator and so p <= numerator
+    let p = u64::try_from(p).expect("the numerator is at most the ratio's numerator");
+    Ratio::new(p, q as u64)
+}
+
+/// Finds the closest fraction to sqrt(n) whose denominator is at most max_denominator
+#[allow(dead_code)]
+pub fn best_sqrt_approximation(n: u64, max_denominator: u64) -> Ratio<u128> {
+    let expansion = sqrt_expansion(n);
+    let n = BigUint::from(n);
+    best_approximation(expansion.terms(), max_denominator, |p, q| {
+        (&n * q * q).cmp(&(p * p))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint};
+
+    use super::{
+        PeriodicContinuedFraction, best_rational_approximation, best_sqrt_approximation,
+        convergents, e_terms, ratio_expansion, sqrt_expansion,
+    };
+    use crate::euler_tools::ratio::Ratio;
+
+    #[test]
+    fn sqrt_expansions() {
+        let expected: [(u64, u64, &[u64]); 8] = [
+            (2, 1, &[2]),
+            (3, 1, &[1, 2]),
+            (4, 2, &[]),
+            (5, 2, &[4]),
+            (7, 2, &[1, 1, 1, 4]),
+            (13, 3, &[1, 1, 1, 1, 6]),
+            (23, 4, &[1, 3, 1, 8]),
+            (0, 0, &[]),
+        ];
+        for (n, integer_part, period) in expected {
+            assert_eq!(
+                sqrt_expansion(n),
+                PeriodicContinuedFraction {
+                    integer_part,
+                    period: period.to_vec()
+                },
+                "sqrt({n})"
+            );
+        }
+        let large = u64::MAX - 1;
+        assert_eq!(sqrt_expansion(large).integer_part, u32::MAX as u64);
+        assert_eq!(
+            sqrt_expansion(large).period.last(),
+            Some(&(2 * u32::MAX as u64))
+        );
+    }
+
+    #[test]
+    fn ratio_expansions() {
+        assert_eq!(ratio_expansion(Ratio::new(415u32, 93)), [4, 2, 6, 7]);
+        assert_eq!(ratio_expansion(Ratio::new(-415i32, 93)), [-5, 1, 1, 6, 7]);
+        assert_eq!(ratio_expansion(Ratio::new(415i32, -93)), [-5, 1, 1, 6, 7]);
+        assert_eq!(ratio_expansion(Ratio::new(6u8, 3)), [2]);
+        assert_eq!(ratio_expansion(Ratio::new(0u8, 3)), [0]);
+        assert_eq!(
+            ratio_expansion(Ratio::new(BigInt::from(-415), BigInt::from(93))),
+            [-5, 1, 1, 6, 7].map(BigInt::from)
+        );
+
+        // the last convergent is the ratio itself, even past what a u64 holds
+        let terms = ratio_expansion(Ratio::new(415u64, 93));
+        assert_eq!(convergents(terms).last(), Some(Ratio::new(415u64, 93)));
+        let big = Ratio::new(BigUint::from(3u8).pow(90), BigUint::from(2u8).pow(100));
+        let terms: Vec<u64> = ratio_expansion(big.clone())
+            .into_iter()
+            .map(|term| u64::try_from(term).unwrap())
+            .collect();
+        assert_eq!(convergents(terms).last(), Some(big));
+    }
+
+    #[test]
+    fn e_convergents() {
+        let found: Vec<Ratio<u64>> = convergents(e_terms()).take(10).collect();
+        let expected = [
+            (2, 1),
+            (3, 1),
+            (8, 3),
+            (11, 4),
+            (19, 7),
+            (87, 32),
+            (106, 39),
+            (193, 71),
+            (1264, 465),
+            (1457, 536),
+        ]
+        .map(|(p, q)| Ratio::new(p, q));
+        assert_eq!(found, expected);
+
+        // u8 convergents stop at overflow, but BigUint ones keep going
+        assert_eq!(convergents::<u8, _>(e_terms()).count(), 8);
+        // 200 + 1/2 overflows, and the 1 after it mustn't be used to make 201/1
+        let mut overflowing = convergents::<u8, _>([200, 2, 1]);
+        assert_eq!(overflowing.next(), Some(Ratio::new_int(200)));
+        assert_eq!(overflowing.next(), None);
+        assert_eq!(overflowing.next(), None);
+        let convergent: Ratio<BigUint> = convergents(e_terms()).nth(99).unwrap();
+        assert_eq!(convergent.numerator().to_string().len(), 58);
+    }
+
+    #[test]
+    fn sqrt_convergents() {
+        let found: Vec<Ratio<u64>> = convergents(sqrt_expansion(2).terms()).take(5).collect();
+        let expected = [(1, 1), (3, 2), (7, 5), (17, 12), (41, 29)].map(|(p, q)| Ratio::new(p, q));
+        assert_eq!(found, expected);
+
+        // every other convergent of sqrt(n) solves Pell's equation at the end of a period
+        let expansion = sqrt_expansion(61);
+        let (p, q): (u128, u128) = convergents(expansion.terms())
+            .nth(2 * expansion.period.len() - 1)
+            .unwrap()
+            .into_parts();
+        assert_eq!(p * p - 61 * q * q, 1);
+    }
+
+    fn brute_force_best(
+        is_closer: impl Fn((u64, u64), (u64, u64)) -> bool,
+        max: u64,
+    ) -> Ratio<u64> {
+        let mut best = (0, 1);
+        for q in 1..=max {
+            for p in 0..=10 * q {
+                if is_closer((p, q), best) {
+                    best = (p, q);
+                }
+            }
+        }
+        Ratio::new(best.0, best.1)
+    }
+
+    #[test]
+    fn best_approximations() {
+        assert_eq!(best_rational_approximation(3, 7, 7), Ratio::new(3, 7));
+        assert_eq!(best_sqrt_approximation(2, 12), Ratio::new(17, 12));
+        assert_eq!(best_sqrt_approximation(2, 11), Ratio::new(7, 5));
+        // the numerator outgrows a u64 long before the denominator does
+        assert_eq!(
+            best_sqrt_approximation(10, u64::MAX),
+            Ratio::new(27701390939331835443, 8759948972303982025)
+        );
+
+        for max in 1..30u64 {
+            for (numerator, denominator) in [(31, 100), (355, 113), (17, 5), (1, 2), (123, 457)] {
+                // compare |p/q - x| exactly, keeping the smaller denominator on ties
+                let distance = |(p, q): (u64, u64)| {
+                    (p * denominator).abs_diff(numerator * q) as f64 / q as f64
+                };
+                let expected = brute_force_best(|a, b| distance(a) < distance(b) - 1e-9, max);
+                let found = best_rational_approximation(numerator, denominator, max);
+                assert_eq!(found, expected, "{numerator}/{denominator} up to {max}");
+            }
+            for n in [2, 3, 5, 10, 13] {
+                let distance = |(p, q): (u64, u64)| (p as f64 / q as f64 - (n as f64).sqrt()).abs();
+                let (p, q) = brute_force_best(|a, b| distance(a) < distance(b), max).into_parts();
+                let expected = Ratio::new(p as u128, q as u128);
+                let found = best_sqrt_approximation(n, max);
+                assert_eq!(found, expected, "sqrt({n}) up to {max}");
+            }
+        }
+    }
+}
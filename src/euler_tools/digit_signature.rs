@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
+
+use crate::euler_tools::{DigitIterator, DigitSource};
+
+/// How many times each digit appears in a number, which two numbers share exactly when their
+/// digits are permutations of each other. Leading zeros aren't counted, so 120 and 012 = 12 have
+/// different signatures.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DigitSignature {
+    radix: u32,
+    counts: DigitCounts,
+}
+
+/// The counts are packed into one u128 whenever they fit, so that most signatures never touch
+/// the heap. Which form a signature takes only depends on its counts, so equal signatures always
+/// compare and hash the same.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum DigitCounts {
+    /// 8 bits per digit, for radixes up to 16 while no digit appears more than 255 times
+    Packed(u128),
+    Spread(Vec<u32>),
+}
+
+impl DigitCounts {
+    const PACKED_BITS: u32 = 8;
+    const PACKED_MAX: u128 = (1 << Self::PACKED_BITS) - 1;
+
+    fn count(&self, digit: u32) -> u32 {
+        match self {
+            DigitCounts::Packed(packed) => {
+                ((packed >> (Self::PACKED_BITS * digit)) & Self::PACKED_MAX) as u32
+            }
+            DigitCounts::Spread(counts) => counts[digit as usize],
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl DigitSignature {
+    pub fn new<I>(number: I) -> Self
+    where
+        I: DigitSource + FromPrimitive,
+        DigitIterator<I>: Iterator<Item: ToPrimitive>,
+    {
+        DigitSignature::new_radix(number, 10)
+    }
+
+    /// Works for any type with a DigitIterator, including BigUint. Negative numbers don't have
+    /// a signature.
+    pub fn new_radix<I>(number: I, radix: u32) -> Self
+    where
+        I: DigitSource + FromPrimitive,
+        DigitIterator<I>: Iterator<Item: ToPrimitive>,
+    {
+        assert!(radix >= 2, "the radix must be at least 2");
+        let radix_value = I::from_u32(radix).expect("the radix should fit in the number's type");
+        let digits = DigitIterator::new_radix(number, radix_value).map(|digit| {
+            digit
+                .to_u32()
+                .expect("negative numbers don't have a signature")
+        });
+        DigitSignature::from_digits(digits, radix)
+    }
+
+    fn from_digits(digits: impl Iterator<Item = u32>, radix: u32) -> Self {
+        let mut counts = if radix <= u128::BITS / DigitCounts::PACKED_BITS {
+            DigitCounts::Packed(0)
+        } else {
+            DigitCounts::Spread(vec![0; radix as usize])
+        };
+        for digit in digits {
+            if let DigitCounts::Packed(_) = counts
+                && counts.count(digit) as u128 == DigitCounts::PACKED_MAX
+            {
+                counts = DigitCounts::Spread((0..radix).map(|digit| counts.count(digit)).collect());
+            }
+            match &mut counts {
+                DigitCounts::Packed(packed) => *packed += 1 << (DigitCounts::PACKED_BITS * digit),
+                DigitCounts::Spread(counts) => counts[digit as usize] += 1,
+            }
+        }
+        DigitSignature { radix, counts }
+    }
+
+    pub fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    /// How many times digit appears
+    pub fn count(&self, digit: u32) -> u32 {
+        assert!(digit < self.radix, "the digit must be less than the radix");
+        self.counts.count(digit)
+    }
+
+    /// The total number of digits
+    pub fn len(&self) -> u32 {
+        (0..self.radix).map(|digit| self.counts.count(digit)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Numbers whose digit signature can be read without giving the number up
+pub trait DigitSignatureSource {
+    fn digit_signature(&self, radix: u32) -> DigitSignature;
+}
+
+macro_rules! digit_signature_source_impl {
+    ($($prim_type:ty),*) => { $(
+        impl DigitSignatureSource for $prim_type {
+            fn digit_signature(&self, radix: u32) -> DigitSignature {
+                DigitSignature::new_radix(*self, radix)
+            }
+        }
+    )* };
+}
+
+digit_signature_source_impl!(u8, u16, u32, u64, u128, usize);
+digit_signature_source_impl!(i8, i16, i32, i64, i128, isize);
+
+impl DigitSignatureSource for BigUint {
+    fn digit_signature(&self, radix: u32) -> DigitSignature {
+        assert!(radix >= 2, "the radix must be at least 2");
+        if radix > 256 {
+            // to_radix_le only goes up to 256
+            return DigitSignature::new_radix(self.clone(), radix);
+        }
+        let digits = if self.is_zero() {
+            Vec::new()
+        } else {
+            self.to_radix_le(radix)
+        };
+        DigitSignature::from_digits(digits.into_iter().map(u32::from), radix)
+    }
+}
+
+/// Buckets numbers by their digit signature in the given radix. The groups come out in the order
+/// their first members went in, and each group keeps its members in order too.
+#[allow(dead_code)]
+pub fn group_by_digit_signature<I>(numbers: impl IntoIterator<Item = I>, radix: u32) -> Vec<Vec<I>>
+where
+    I: DigitSignatureSource,
+{
+    let mut group_indices: HashMap<DigitSignature, usize> = HashMap::new();
+    let mut groups: Vec<Vec<I>> = Vec::new();
+    for number in numbers {
+        let signature = number.digit_signature(radix);
+        let next_index = groups.len();
+        let index = *group_indices.entry(signature).or_insert(next_index);
+        if index == next_index {
+            groups.push(Vec::new());
+        }
+        groups[index].push(number);
+    }
+    groups
+}
+
+/// The groups of exactly group_size numbers whose decimal digits are permutations of each other
+#[allow(dead_code)]
+pub fn digit_permutation_groups<I>(
+    numbers: impl IntoIterator<Item = I>,
+    group_size: usize,
+) -> Vec<Vec<I>>
+where
+    I: DigitSignatureSource,
+{
+    group_by_digit_signature(numbers, 10)
+        .into_iter()
+        .filter(|group| group.len() == group_size)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{
+        DigitSignature, DigitSignatureSource, digit_permutation_groups, group_by_digit_signature,
+    };
+
+    #[test]
+    fn signatures() {
+        assert_eq!(DigitSignature::new(1487u32), DigitSignature::new(4817u32));
+        assert_eq!(DigitSignature::new(1487u32), DigitSignature::new(8147i64));
+        assert_ne!(DigitSignature::new(1487u32), DigitSignature::new(1477u32));
+        assert_ne!(DigitSignature::new(120u32), DigitSignature::new(12u32));
+        assert_eq!(DigitSignature::new(0u8).len(), 0);
+        assert!(DigitSignature::new(0u8).is_empty());
+
+        let signature = DigitSignature::new(1_000_100u64);
+        assert_eq!(
+            (signature.count(0), signature.count(1), signature.len()),
+            (5, 2, 7)
+        );
+
+        // 0b1011 and 0b1101 are permutations in binary but 11 and 13 aren't in decimal
+        assert_eq!(
+            DigitSignature::new_radix(11u8, 2),
+            DigitSignature::new_radix(13u8, 2)
+        );
+        assert_ne!(DigitSignature::new(11u8), DigitSignature::new(13u8));
+        assert_ne!(
+            DigitSignature::new_radix(0u8, 2),
+            DigitSignature::new_radix(0u8, 3)
+        );
+
+        let big = BigUint::from(12_345_678_901_234_567_890u128) * 1_000_000_000u64;
+        let shuffled: BigUint = "98765432100000000000123456789".parse().unwrap();
+        assert_eq!(DigitSignature::new(big), DigitSignature::new(shuffled));
+        assert_eq!(
+            DigitSignature::new(BigUint::from(4817u32)),
+            DigitSignature::new(1487u64)
+        );
+        let hex = DigitSignature::new_radix(BigUint::from(0xabcu32), 16);
+        assert_eq!(hex, DigitSignature::new_radix(0xcabu64, 16));
+        assert_eq!(hex.radix(), 16);
+
+        // reading a signature by reference agrees with consuming the number
+        assert_eq!(
+            BigUint::from(4817u32).digit_signature(10),
+            DigitSignature::new(BigUint::from(1487u32))
+        );
+        assert_eq!(
+            BigUint::from(0u8).digit_signature(10),
+            DigitSignature::new(0u8)
+        );
+        assert_eq!(
+            BigUint::from(789_456_123u32).digit_signature(1000),
+            DigitSignature::new_radix(123_456_789u64, 1000)
+        );
+    }
+
+    #[test]
+    fn many_repeated_digits() {
+        // more than 255 of one digit doesn't fit in the packed counts any more
+        let number = |text: String| text.parse::<BigUint>().unwrap();
+        let ones = |count: usize| "1".repeat(count);
+        let trailing_zero = number(ones(256) + "0");
+        let middle_zero = number(ones(128) + "0" + &ones(128));
+        assert_eq!(
+            trailing_zero.digit_signature(10),
+            middle_zero.digit_signature(10)
+        );
+        let signature = DigitSignature::new(trailing_zero);
+        assert_eq!((signature.count(0), signature.count(1)), (1, 256));
+        assert_eq!(signature.len(), 257);
+        assert_ne!(
+            number(ones(255) + "0").digit_signature(10),
+            middle_zero.digit_signature(10)
+        );
+        assert_eq!(number(ones(255)).digit_signature(10).count(1), 255);
+    }
+
+    #[test]
+    fn groups() {
+        let groups = group_by_digit_signature([12u32, 31, 21, 13, 5, 113, 311], 10);
+        assert_eq!(
+            groups,
+            [vec![12, 21], vec![31, 13], vec![5], vec![113, 311]]
+        );
+
+        // 345^3 = 41063625, 384^3 = 56623104 and 405^3 = 66430125
+        let cubes = (215u64..465).map(|n| n.pow(3));
+        let triples = digit_permutation_groups(cubes, 3);
+        assert_eq!(triples[0], [41_063_625, 56_623_104, 66_430_125]);
+
+        // 12^2 = 144 and 21^2 = 441, while 169, 196 and 961 make a group of three
+        let big_squares = (1u32..100).map(|n| BigUint::from(n).pow(2));
+        let pairs = digit_permutation_groups(big_squares, 2);
+        assert_eq!(pairs[0], [BigUint::from(144u32), BigUint::from(441u32)]);
+    }
+}
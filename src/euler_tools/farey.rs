@@ -0,0 +1,365 @@
+//! Farey sequences and the Stern–Brocot tree. `CoprimePairsIterator` already walks F_n in order
+//! (without 0/1), but it keeps a stack of pending mediants that grows to n entries. The
+//! next-term recurrence in `FareySequence` needs O(1) space, and the neighbour search descends
+//! the tree directly, so neither is built on it.
+
+use std::cmp::Ordering;
+
+use crate::euler_tools::{
+    prime_finder::{mobius_sieve, totient_sum},
+    ratio::Ratio,
+};
+
+/// Iterates over the Farey sequence F_n, every reduced fraction in [0, 1] with a denominator of
+/// at most n, in increasing order. Each term takes O(1) steps.
+pub struct FareySequence {
+    order: u64,
+    current: Option<(u64, u64)>,
+    next: (u64, u64),
+}
+
+impl Iterator for FareySequence {
+    type Item = Ratio<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (a, b) = self.current?;
+        if a == b {
+            self.current = None;
+        } else {
+            // a/b, c/d, e/f are consecutive exactly when e = kc - a and f = kd - b, where k is as
+            // big as the order allows
+            let (c, d) = self.next;
+            let k = (self.order + b) / d;
+            self.current = Some((c, d));
+            self.next = (k * c - a, k * d - b);
+        }
+        Some(Ratio::new(a, b))
+    }
+}
+
+#[allow(dead_code)]
+pub fn farey_sequence(order: u64) -> FareySequence {
+    assert!(order > 0, "the Farey sequence of order 0 is empty");
+    FareySequence {
+        order,
+        current: Some((0, 1)),
+        next: (1, order),
+    }
+}
+
+/// The number of terms in F_n, which is 1 + phi(1) + ... + phi(n)
+#[allow(dead_code)]
+pub fn farey_length(order: u64) -> u128 {
+    1 + totient_sum(order)
+}
+
+/// Compares p/q to a/b without overflow
+fn compare(p: u128, q: u128, a: u128, b: u128) -> Ordering {
+    (p * b).cmp(&(a * q))
+}
+
+/// The fractions immediately before and after x in the Farey sequence F_n, not counting x
+/// itself. x doesn't have to be in F_n, but it does have to be in [0, 1].
+///
+/// This descends the Stern–Brocot tree from 0/1 and 1/1, taking as many steps in the same
+/// direction as it can at once, so it only takes O(log n) steps.
+#[allow(dead_code)]
+pub fn farey_neighbours(x: Ratio<u64>, order: u64) -> (Option<Ratio<u64>>, Option<Ratio<u64>>) {
+    assert!(order > 0, "the Farey sequence of order 0 is empty");
+    let (a, b) = (*x.numerator() as u128, *x.denominator() as u128);
+    assert!(a <= b, "x must be between 0 and 1");
+    let n = order as u128;
+    let ratio = |(p, q): (u128, u128)| Ratio::new(p as u64, q as u64);
+
+    if a == 0 {
+        return (None, Some(Ratio::new(1, order)));
+    }
+    if a == b {
+        return (Some(Ratio::new(order - 1, order)), None);
+    }
+
+    // left < x < right, and the two are always neighbours in some Farey sequence
+    let (mut left, mut right) = ((0u128, 1u128), (1u128, 1u128));
+    loop {
+        if left.1 + right.1 > n {
+            // nothing between the two fits in F_n
+            return (Some(ratio(left)), Some(ratio(right)));
+        }
+        let mediant = (left.0 + right.0, left.1 + right.1);
+        // left + t right < x exactly when t (right - x) < (x - left), scaled by the denominators
+        let above = right.0 * b - a * right.1;
+        let below = a * left.1 - b * left.0;
+        match compare(mediant.0, mediant.1, a, b) {
+            Ordering::Equal => {
+                // x is in F_n, and its neighbours come from adding x to its parents as many times
+                // as the order allows
+                let k = (n - left.1) / b;
+                let previous = (left.0 + k * a, left.1 + k * b);
+                let k = (n - right.1) / b;
+                let next = (right.0 + k * a, right.1 + k * b);
+                return (Some(ratio(previous)), Some(ratio(next)));
+            }
+            Ordering::Less => {
+                let t = ((below - 1) / above).min((n - left.1) / right.1);
+                left = (left.0 + t * right.0, left.1 + t * right.1);
+            }
+            Ordering::Greater => {
+                let t = ((above - 1) / below).min((n - right.1) / left.1);
+                right = (right.0 + t * left.0, right.1 + t * left.1);
+            }
+        }
+    }
+}
+
+/// The fraction closest to x with a denominator of at most max_denominator, found by descending
+/// the Stern–Brocot tree. Ties go to the smaller denominator.
+#[allow(dead_code)]
+pub fn stern_brocot_approximation(x: Ratio<u64>, max_denominator: u64) -> Ratio<u64> {
+    assert!(max_denominator > 0, "max_denominator must be positive");
+    if *x.denominator() <= max_denominator {
+        return x;
+    }
+    // x isn't an integer, so its fractional part is strictly between its neighbours
+    let whole = x.floor();
+    let fraction = x - whole;
+    let (Some(below), Some(above)) = farey_neighbours(fraction, max_denominator) else {
+        unreachable!("a fraction in (0, 1) has neighbours on both sides")
+    };
+    let closer = match (fraction - below).cmp(&(above - fraction)) {
+        Ordering::Less => below,
+        Ordering::Greater => above,
+        Ordering::Equal if above.denominator() < below.denominator() => above,
+        Ordering::Equal => below,
+    };
+    closer + whole
+}
+
+/// sum of (a i + c) / m for i in 0..n, rounding each term down, in O(log m) steps
+fn floor_sum(n: u128, m: u128, a: u128, c: u128) -> u128 {
+    let (mut n, mut m, mut a, mut c) = (n, m, a, c);
+    let mut sum = 0;
+    loop {
+        if a >= m {
+            sum += n * (n - 1) / 2 * (a / m);
+            a %= m;
+        }
+        if c >= m {
+            sum += n * (c / m);
+            c %= m;
+        }
+        // count the lattice points under the line the other way around
+        let y_max = a * n + c;
+        if y_max < m {
+            return sum;
+        }
+        (n, c) = (y_max / m, y_max % m);
+        (m, a) = (a, m);
+    }
+}
+
+/// The number of reduced fractions in (0, x] with a denominator of at most n. By Möbius
+/// inversion this is the sum of mu(d) G(n / d), where G(m) counts every fraction p/q in (0, x]
+/// with q <= m, reduced or not.
+fn count_up_to(x: Ratio<u64>, order: u64, mobius_sums: &[i64]) -> u128 {
+    let (a, b) = (*x.numerator() as u128, *x.denominator() as u128);
+    let all_fractions = |m: u64| floor_sum(m as u128 + 1, b, a, 0) as i128;
+
+    // n / d only takes O(sqrt(n)) values, so group the d that share one
+    let mut count = 0i128;
+    let mut d = 1;
+    while d <= order {
+        let quotient = order / d;
+        let last = order / quotient;
+        let mobius = (mobius_sums[last as usize] - mobius_sums[d as usize - 1]) as i128;
+        count += mobius * all_fractions(quotient);
+        d = last + 1;
+    }
+    count as u128
+}
+
+/// The number of terms of F_n in the closed interval [lower, upper], in O(n) steps
+#[allow(dead_code)]
+pub fn farey_count_in_interval(lower: Ratio<u64>, upper: Ratio<u64>, order: u64) -> u128 {
+    assert!(lower <= upper, "the interval must not be empty");
+    assert!(
+        *upper.numerator() <= *upper.denominator(),
+        "the interval must be inside [0, 1]"
+    );
+    let mobius_sums: Vec<i64> = mobius_sieve(order as usize + 1)
+        .into_iter()
+        .scan(0, |sum, mu| {
+            *sum += mu as i64;
+            Some(*sum)
+        })
+        .collect();
+    // the lower end is only counted if it's in F_n, and 0/1 always is
+    let includes_lower = *lower.denominator() <= order;
+    count_up_to(upper, order, &mobius_sums) - count_up_to(lower, order, &mobius_sums)
+        + includes_lower as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        farey_count_in_interval, farey_length, farey_neighbours, farey_sequence,
+        stern_brocot_approximation,
+    };
+    use crate::euler_tools::{
+        continued_fraction::best_rational_approximation, prime_finder::CoprimePairsIterator,
+        ratio::Ratio,
+    };
+
+    /// Every reduced fraction in [0, 1] with a denominator of at most n, sorted
+    fn brute_force_farey(order: u64) -> Vec<Ratio<u64>> {
+        let mut fractions: Vec<Ratio<u64>> = (1..=order)
+            .flat_map(|q| (0..=q).map(move |p| Ratio::new(p, q)))
+            .collect();
+        fractions.sort();
+        fractions.dedup();
+        fractions
+    }
+
+    #[test]
+    fn sequences() {
+        let f5: Vec<String> = farey_sequence(5).map(|x| x.to_string()).collect();
+        assert_eq!(
+            f5,
+            [
+                "0", "1/5", "1/4", "1/3", "2/5", "1/2", "3/5", "2/3", "3/4", "4/5", "1"
+            ]
+        );
+        assert_eq!(farey_sequence(1).count(), 2);
+
+        // CoprimePairsIterator walks the same fractions in the same order, apart from 0/1
+        let pairs: Vec<Ratio<u64>> = CoprimePairsIterator::new(51)
+            .map(|(p, q)| Ratio::new(p, q))
+            .collect();
+        assert_eq!(farey_sequence(50).skip(1).collect::<Vec<_>>(), pairs);
+
+        for order in 1..40 {
+            let expected = brute_force_farey(order);
+            let found: Vec<Ratio<u64>> = farey_sequence(order).collect();
+            assert_eq!(found, expected, "F_{order}");
+            assert_eq!(farey_length(order), expected.len() as u128);
+        }
+    }
+
+    #[test]
+    fn neighbours() {
+        assert_eq!(
+            farey_neighbours(Ratio::new(3, 7), 8),
+            (Some(Ratio::new(2, 5)), Some(Ratio::new(1, 2)))
+        );
+        assert_eq!(
+            farey_neighbours(Ratio::new(3, 7), 1_000_000).0,
+            Some(Ratio::new(428_570, 999_997))
+        );
+
+        for order in 1..25 {
+            let sequence = brute_force_farey(order);
+            for window in sequence.windows(3) {
+                let expected = (Some(window[0]), Some(window[2]));
+                assert_eq!(
+                    farey_neighbours(window[1], order),
+                    expected,
+                    "{} in F_{order}",
+                    window[1]
+                );
+            }
+            assert_eq!(
+                farey_neighbours(sequence[0], order),
+                (None, Some(sequence[1]))
+            );
+            let last = sequence.len() - 1;
+            assert_eq!(
+                farey_neighbours(sequence[last], order),
+                (Some(sequence[last - 1]), None)
+            );
+
+            // fractions that aren't in F_n land between two terms
+            for x in brute_force_farey(order + 7) {
+                if *x.denominator() <= order {
+                    continue;
+                }
+                let position = sequence.partition_point(|y| *y < x);
+                let expected = (Some(sequence[position - 1]), Some(sequence[position]));
+                assert_eq!(farey_neighbours(x, order), expected, "{x} around F_{order}");
+            }
+        }
+    }
+
+    #[test]
+    fn approximations() {
+        assert_eq!(
+            stern_brocot_approximation(Ratio::new(355, 113), 100),
+            Ratio::new(311, 99)
+        );
+        assert_eq!(
+            stern_brocot_approximation(Ratio::new(355, 113), 7),
+            Ratio::new(22, 7)
+        );
+        assert_eq!(
+            stern_brocot_approximation(Ratio::new(7, 2), 1),
+            Ratio::new(3, 1)
+        );
+        for max in 1..30 {
+            for (p, q) in [
+                (31, 100),
+                (355, 113),
+                (17, 5),
+                (1, 2),
+                (123, 457),
+                (1, 1000),
+            ] {
+                assert_eq!(
+                    stern_brocot_approximation(Ratio::new(p, q), max),
+                    best_rational_approximation(p, q, max),
+                    "{p}/{q} up to {max}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn interval_counts() {
+        // 1/3 and 1/2 and the 3 fractions between them in F_8
+        assert_eq!(
+            farey_count_in_interval(Ratio::new(1, 3), Ratio::new(1, 2), 8),
+            5
+        );
+        assert_eq!(
+            farey_count_in_interval(Ratio::new(0, 1), Ratio::new(1, 1), 1000),
+            farey_length(1000)
+        );
+
+        let bounds = [
+            (0, 1),
+            (1, 7),
+            (1, 3),
+            (2, 5),
+            (3, 7),
+            (1, 2),
+            (5, 8),
+            (13, 17),
+            (1, 1),
+        ];
+        for order in 1..40 {
+            let sequence = brute_force_farey(order);
+            for (i, lower) in bounds.iter().enumerate() {
+                for upper in &bounds[i..] {
+                    let (lower, upper) =
+                        (Ratio::new(lower.0, lower.1), Ratio::new(upper.0, upper.1));
+                    let expected = sequence
+                        .iter()
+                        .filter(|x| lower <= **x && **x <= upper)
+                        .count();
+                    assert_eq!(
+                        farey_count_in_interval(lower, upper, order),
+                        expected as u128,
+                        "[{lower}, {upper}] in F_{order}"
+                    );
+                }
+            }
+        }
+    }
+}
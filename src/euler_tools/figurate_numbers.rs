@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use integer_sqrt::IntegerSquareRoot;
 
 #[allow(dead_code)]
@@ -48,6 +50,405 @@ where
     }
 }
 
+/// s-gonal numbers for any number of sides s >= 3, with exact integer arithmetic throughout.
+/// P(s, n) = n + (s - 2) n (n - 1) / 2, so triangles are s = 3, squares are s = 4, and so on.
+pub trait Polygonal: Sized {
+    /// The nth s-gonal number. Panics if it doesn't fit.
+    #[allow(dead_code)]
+    fn polygonal(n: Self, sides: Self) -> Self;
+    /// The nth s-gonal number, or None if it doesn't fit
+    fn checked_polygonal(n: Self, sides: Self) -> Option<Self>;
+    /// The n >= 0 with P(s, n) = x, if there is one
+    fn inverse_polygonal(x: Self, sides: Self) -> Option<Self>;
+    fn is_polygonal(x: Self, sides: Self) -> bool;
+}
+macro_rules! polygonal_impl {
+    ( $($prim_type:ty),* ) => { $(
+        impl Polygonal for $prim_type {
+            fn polygonal(n: Self, sides: Self) -> Self {
+                Polygonal::checked_polygonal(n, sides).expect("polygonal number overflowed")
+            }
+
+            fn checked_polygonal(n: Self, sides: Self) -> Option<Self> {
+                assert!(sides >= 3, "polygons need at least 3 sides");
+                if n == 0 {
+                    return Some(0);
+                }
+                // halve whichever of n and n - 1 is even, so nothing overflows unless the answer
+                // does
+                let triangle = if n % 2 == 0 {
+                    (n / 2).checked_mul(n - 1)?
+                } else {
+                    n.checked_mul((n - 1) / 2)?
+                };
+                (sides - 2).checked_mul(triangle)?.checked_add(n)
+            }
+
+            #[allow(unused_comparisons)]
+            fn inverse_polygonal(x: Self, sides: Self) -> Option<Self> {
+                assert!(sides >= 3, "polygons need at least 3 sides");
+                if x < 0 {
+                    return None;
+                }
+                // 8 (s - 2) x + (s - 4)^2 = (2 (s - 2) n - (s - 4))^2, so n comes straight from the
+                // square root unless some step of that overflows
+                let closed_form = || -> Option<Self> {
+                    let step = sides - 2;
+                    let offset = sides.abs_diff(4) as Self;
+                    let discriminant = (8 as Self)
+                        .checked_mul(step)?
+                        .checked_mul(x)?
+                        .checked_add(offset.checked_mul(offset)?)?;
+                    let root = discriminant.integer_sqrt();
+                    // with 3 sides the discriminant is 8x + 1, so the root is at least 1
+                    let numerator = if sides >= 4 {
+                        root.checked_add(sides - 4)?
+                    } else {
+                        root - 1
+                    };
+                    Some(numerator / step.checked_mul(2)?)
+                };
+                let n = closed_form().unwrap_or_else(|| {
+                    // P(s, n) >= n^2 / 2, so n is at most sqrt(2x) < 2 sqrt(x) + 2
+                    let (mut low, mut high) = (0, x.integer_sqrt() * 2 + 2);
+                    while low < high {
+                        let middle = low + (high - low) / 2;
+                        match Polygonal::checked_polygonal(middle, sides) {
+                            Some(value) if value < x => low = middle + 1,
+                            _ => high = middle,
+                        }
+                    }
+                    low
+                });
+                (Polygonal::checked_polygonal(n, sides) == Some(x)).then_some(n)
+            }
+
+            fn is_polygonal(x: Self, sides: Self) -> bool {
+                Polygonal::inverse_polygonal(x, sides).is_some()
+            }
+        }
+    )* };
+}
+polygonal_impl!(u8, u16, u32, u64, u128, usize);
+polygonal_impl!(i8, i16, i32, i64, i128, isize);
+
+/// A family of figurate numbers, indexed from 1 so that the first member of every family is 1.
+/// Values are u64, with the arithmetic done in u128 so that it's exact whenever the answer fits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(dead_code)]
+pub enum FigurateFamily {
+    /// s-gonal numbers 1, s, 3s - 3, ...
+    Polygonal(u64),
+    /// A dot in the middle with s-gons around it: s n (n - 1) / 2 + 1
+    CenteredPolygonal(u64),
+    /// Stacked s-gonal numbers: n (n + 1) ((s - 2) n - (s - 5)) / 6
+    Pyramidal(u64),
+    /// n (2 n^2 + 1) / 3
+    Octahedral,
+    /// Six-pointed stars, 6 n (n - 1) + 1
+    Star,
+}
+
+#[allow(dead_code)]
+impl FigurateFamily {
+    pub const TRIANGULAR: Self = FigurateFamily::Polygonal(3);
+    pub const SQUARE: Self = FigurateFamily::Polygonal(4);
+    pub const PENTAGONAL: Self = FigurateFamily::Polygonal(5);
+    pub const HEXAGONAL: Self = FigurateFamily::Polygonal(6);
+    pub const HEPTAGONAL: Self = FigurateFamily::Polygonal(7);
+    pub const OCTAGONAL: Self = FigurateFamily::Polygonal(8);
+    pub const TETRAHEDRAL: Self = FigurateFamily::Pyramidal(3);
+    pub const SQUARE_PYRAMIDAL: Self = FigurateFamily::Pyramidal(4);
+
+    /// The nth member, counting from n = 1, or None if it doesn't fit in a u64
+    pub fn nth(self, n: u64) -> Option<u64> {
+        assert!(n > 0, "figurate numbers are indexed from 1");
+        let n = n as u128;
+        let value = match self {
+            FigurateFamily::Polygonal(sides) => {
+                return Polygonal::checked_polygonal(n as u64, sides);
+            }
+            FigurateFamily::CenteredPolygonal(sides) => {
+                assert!(sides >= 3, "polygons need at least 3 sides");
+                (sides as u128).checked_mul(n * (n - 1) / 2)? + 1
+            }
+            FigurateFamily::Pyramidal(sides) => {
+                assert!(sides >= 3, "polygons need at least 3 sides");
+                // (s - 2) n - (s - 5) is positive for every n >= 1
+                let last_factor =
+                    ((sides as u128 - 2).checked_mul(n)? + 5).checked_sub(sides as u128)?;
+                (n * (n + 1) / 2).checked_mul(last_factor)? / 3
+            }
+            FigurateFamily::Octahedral => n.checked_mul(n.checked_mul(n)?.checked_mul(2)? + 1)? / 3,
+            FigurateFamily::Star => n.checked_mul(n - 1)?.checked_mul(6)? + 1,
+        };
+        value.try_into().ok()
+    }
+
+    /// The number of members that are at most x, which is also the index of the largest one
+    fn count_up_to(self, x: u64) -> u64 {
+        let fits = |n: u64| self.nth(n).is_some_and(|value| value <= x);
+        if !fits(1) {
+            return 0;
+        }
+        // double until the index is too big, then binary search back down
+        let mut high = 2;
+        while fits(high) {
+            high *= 2;
+        }
+        let mut low = high / 2;
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if fits(middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    /// The n with nth(n) = x, if x is a member
+    pub fn index_of(self, x: u64) -> Option<u64> {
+        let n = self.count_up_to(x);
+        (n > 0 && self.nth(n) == Some(x)).then_some(n)
+    }
+
+    pub fn contains(self, x: u64) -> bool {
+        self.index_of(x).is_some()
+    }
+
+    /// The smallest member that's at least x, if it fits in a u64
+    pub fn next_member(self, x: u64) -> Option<u64> {
+        match x {
+            0 => Some(1),
+            _ => self.nth(self.count_up_to(x - 1) + 1),
+        }
+    }
+
+    /// Every member in increasing order, stopping before one overflows
+    pub fn members(self) -> FigurateMembers {
+        FigurateMembers {
+            family: self,
+            next_index: Some(1),
+            last: u64::MAX,
+        }
+    }
+
+    /// The members with exactly the given number of decimal digits, in increasing order
+    pub fn members_with_digits(self, digits: u32) -> FigurateMembers {
+        assert!(digits > 0, "members have at least one digit");
+        let first = 10u64.checked_pow(digits - 1);
+        FigurateMembers {
+            family: self,
+            next_index: first.map(|first| self.count_up_to(first - 1) + 1),
+            last: 10u64.checked_pow(digits).map_or(u64::MAX, |end| end - 1),
+        }
+    }
+}
+
+pub struct FigurateMembers {
+    family: FigurateFamily,
+    next_index: Option<u64>,
+    last: u64,
+}
+
+impl Iterator for FigurateMembers {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.next_index?;
+        let value = self.family.nth(n).filter(|value| *value <= self.last);
+        self.next_index = value.and_then(|_| n.checked_add(1));
+        value
+    }
+}
+
+/// Iterates over the numbers that belong to every one of a set of families, in increasing order.
+/// Each family jumps straight to its next member at or above the current candidate, so sparse
+/// families drive the search.
+pub struct FigurateIntersection {
+    families: Vec<FigurateFamily>,
+    candidate: Option<u64>,
+}
+
+impl Iterator for FigurateIntersection {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut candidate = self.candidate?;
+        // once every family agrees on the candidate, it's in all of them
+        let mut agreeing = 0;
+        let mut family_index = 0;
+        while agreeing < self.families.len() {
+            let member = self.families[family_index].next_member(candidate);
+            let Some(member) = member else {
+                self.candidate = None;
+                return None;
+            };
+            if member == candidate {
+                agreeing += 1;
+            } else {
+                candidate = member;
+                agreeing = 1;
+            }
+            family_index = (family_index + 1) % self.families.len();
+        }
+        self.candidate = candidate.checked_add(1);
+        Some(candidate)
+    }
+}
+
+#[allow(dead_code)]
+pub fn figurate_intersection(families: &[FigurateFamily]) -> FigurateIntersection {
+    assert!(
+        !families.is_empty(),
+        "the intersection needs at least one family"
+    );
+    FigurateIntersection {
+        families: families.to_vec(),
+        candidate: Some(1),
+    }
+}
+
+/// A member of one of the families in an OverlapGraph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FigurateNode {
+    pub family: FigurateFamily,
+    pub value: u64,
+}
+
+/// The members of several families that have the same number of digits, with an edge from a to
+/// b when the last digits of a are the first digits of b and the two are different numbers from
+/// different families. A number in more than one family gets a node for each.
+pub struct OverlapGraph {
+    families: Vec<FigurateFamily>,
+    /// the index of each node's family, and its value
+    nodes: Vec<(usize, u64)>,
+    successors: Vec<Vec<usize>>,
+}
+
+#[allow(dead_code)]
+impl OverlapGraph {
+    pub fn nodes(&self) -> impl Iterator<Item = FigurateNode> + '_ {
+        (0..self.nodes.len()).map(|node| self.node(node))
+    }
+
+    fn node(&self, node: usize) -> FigurateNode {
+        let (family, value) = self.nodes[node];
+        FigurateNode {
+            family: self.families[family],
+            value,
+        }
+    }
+
+    /// The nodes that an edge leads to from the given one
+    pub fn successors(&self, node: FigurateNode) -> Vec<FigurateNode> {
+        let position = self.nodes().position(|other| other == node);
+        position.map_or(Vec::new(), |position| {
+            self.successors[position]
+                .iter()
+                .map(|next| self.node(*next))
+                .collect()
+        })
+    }
+
+    /// Every cycle of the given length that uses each family at most once. Each cycle is listed
+    /// once, starting from whichever of its nodes was built first, so the earliest family in the
+    /// cycle comes first.
+    pub fn cycles(&self, length: usize) -> Vec<Vec<FigurateNode>> {
+        assert!(length > 0, "cycles need at least one node");
+        let mut cycles = Vec::new();
+        for start in 0..self.nodes.len() {
+            let used_families = 1 << self.nodes[start].0;
+            self.extend_cycles(&mut vec![start], used_families, length, &mut cycles);
+        }
+        cycles
+    }
+
+    fn extend_cycles(
+        &self,
+        path: &mut Vec<usize>,
+        used_families: u64,
+        length: usize,
+        cycles: &mut Vec<Vec<FigurateNode>>,
+    ) {
+        let last = path[path.len() - 1];
+        if path.len() == length {
+            if self.successors[last].contains(&path[0]) {
+                cycles.push(path.iter().map(|node| self.node(*node)).collect());
+            }
+            return;
+        }
+        for next in self.successors[last].iter() {
+            let (family, value) = self.nodes[*next];
+            // only the rotation that starts at its first node gets counted
+            if *next <= path[0]
+                || used_families & (1 << family) != 0
+                || path.iter().any(|node| self.nodes[*node].1 == value)
+            {
+                continue;
+            }
+            path.push(*next);
+            self.extend_cycles(path, used_families | (1 << family), length, cycles);
+            path.pop();
+        }
+    }
+}
+
+/// Builds the OverlapGraph of the members of each family with the given number of digits, where
+/// consecutive numbers share overlap digits
+#[allow(dead_code)]
+pub fn overlap_graph(families: &[FigurateFamily], digits: u32, overlap: u32) -> OverlapGraph {
+    assert!(families.len() <= 64, "at most 64 families are supported");
+    assert!(
+        0 < overlap && overlap < digits,
+        "the overlap must be shorter than the numbers"
+    );
+    let nodes: Vec<(usize, u64)> = families
+        .iter()
+        .enumerate()
+        .flat_map(|(index, family)| {
+            family
+                .members_with_digits(digits)
+                .map(move |value| (index, value))
+        })
+        .collect();
+
+    let prefix_divisor = 10u64.pow(digits - overlap);
+    let suffix_modulus = 10u64.pow(overlap);
+    let mut by_prefix: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (node, (_, value)) in nodes.iter().enumerate() {
+        by_prefix
+            .entry(value / prefix_divisor)
+            .or_default()
+            .push(node);
+    }
+    let successors = nodes
+        .iter()
+        .map(|(family, value)| {
+            by_prefix
+                .get(&(value % suffix_modulus))
+                .map_or(Vec::new(), |candidates| {
+                    candidates
+                        .iter()
+                        .filter(|next| {
+                            let (next_family, next_value) = nodes[**next];
+                            next_family != *family && next_value != *value
+                        })
+                        .copied()
+                        .collect()
+                })
+        })
+        .collect();
+
+    OverlapGraph {
+        families: families.to_vec(),
+        nodes,
+        successors,
+    }
+}
+
 pub trait Triangle {
     fn triangle(n: Self) -> Self;
     fn inverse_triangle(n: Self) -> f64;
@@ -118,8 +519,8 @@ macro_rules! pentagon_impl {
                 // NOTE: if a number is pentagonal, (sqrt(24x + 1) + 1) 6 is the index
             }
             fn is_pentagonal(pentagon: Self) -> bool {
-                let inverse_pentagon = Pentagon::inverse_pentagon(pentagon);
-                inverse_pentagon == inverse_pentagon.trunc()
+                // f64 can't tell large pentagonal numbers from their neighbours
+                Polygonal::is_polygonal(pentagon, 5)
             }
         }
     )* };
@@ -442,4 +843,290 @@ mod test_figurate_numbers {
             "Octagon",
         );
     }
+
+    #[test]
+    fn polygonal_matches_named_shapes() {
+        use super::Polygonal;
+
+        let known = [
+            known_u32_triangles(),
+            known_u32_squares(),
+            known_u32_pentagons(),
+            known_u32_hexagons(),
+            known_u32_heptagons(),
+            known_u32_octagons(),
+        ];
+        for (sides, values) in (3..).zip(known) {
+            for (n, value) in values.iter().enumerate() {
+                assert_eq!(
+                    Polygonal::polygonal(n as u32, sides),
+                    *value,
+                    "{sides}-gon {n}"
+                );
+                assert_eq!(Polygonal::inverse_polygonal(*value, sides), Some(n as u32));
+                assert_eq!(
+                    Polygonal::inverse_polygonal(*value as i64, sides as i64),
+                    Some(n as i64)
+                );
+            }
+            let last = *values.last().unwrap();
+            let members = (0..=last)
+                .filter(|x| Polygonal::is_polygonal(*x, sides))
+                .count();
+            assert_eq!(members, values.len(), "{sides}-gons up to {last}");
+        }
+    }
+
+    #[test]
+    fn polygonal_is_exact() {
+        use super::{Pentagon, Polygonal};
+
+        // f64 can't tell these apart from their neighbours
+        let n = 3_000_000_000u64;
+        let pentagon: u64 = Polygonal::polygonal(n, 5);
+        assert_eq!(Polygonal::inverse_polygonal(pentagon, 5), Some(n));
+        assert!(!Polygonal::is_polygonal(pentagon + 1, 5));
+        assert!(!Pentagon::is_pentagonal(pentagon - 1));
+        assert!(Pentagon::is_pentagonal(pentagon));
+
+        // the discriminant overflows here, so this takes the binary search
+        let n = (1u128 << 64) - 1;
+        let triangle: u128 = Polygonal::polygonal(n, 3);
+        assert_eq!(Polygonal::inverse_polygonal(triangle, 3), Some(n));
+        assert_eq!(Polygonal::inverse_polygonal(triangle - 1, 3), None);
+        assert_eq!(Polygonal::inverse_polygonal(u128::MAX, 3), None);
+        assert_eq!(Polygonal::checked_polygonal(1u128 << 65, 3), None);
+
+        // sides beyond the named shapes, like the 1000-gonal numbers 0, 1, 1000, 2997
+        assert_eq!(Polygonal::polygonal(3u64, 1000), 2997);
+        assert_eq!(Polygonal::inverse_polygonal(2997u64, 1000), Some(3));
+        assert_eq!(Polygonal::inverse_polygonal(2996u64, 1000), None);
+        assert_eq!(Polygonal::checked_polygonal(200u8, 3), None);
+        assert_eq!(Polygonal::checked_polygonal(22u8, 3), Some(253));
+        assert_eq!(Polygonal::inverse_polygonal(-5i32, 4), None);
+        assert_eq!(Polygonal::polygonal(-2i32, 5), 7);
+        // 1 is the first member of every family, even when the closed form overflows
+        assert_eq!(Polygonal::inverse_polygonal(1u8, 20), Some(1));
+        assert_eq!(Polygonal::inverse_polygonal(1u8, u8::MAX), Some(1));
+        assert_eq!(Polygonal::inverse_polygonal(1i8, i8::MAX), Some(1));
+        for x in 0..=u8::MAX {
+            for sides in 3..=u8::MAX {
+                let expected = (0..=x).find(|n| Polygonal::checked_polygonal(*n, sides) == Some(x));
+                assert_eq!(
+                    Polygonal::inverse_polygonal(x, sides),
+                    expected,
+                    "{x} with {sides} sides"
+                );
+            }
+        }
+        for x in i8::MIN..=i8::MAX {
+            for sides in 3..=i8::MAX {
+                let expected =
+                    (0..=x.max(0)).find(|n| Polygonal::checked_polygonal(*n, sides) == Some(x));
+                assert_eq!(
+                    Polygonal::inverse_polygonal(x, sides),
+                    expected,
+                    "{x} with {sides} sides"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn families_match_known_values() {
+        use super::FigurateFamily;
+        let known: [(FigurateFamily, [u64; 8]); 7] = [
+            (
+                FigurateFamily::CenteredPolygonal(3),
+                [1, 4, 10, 19, 31, 46, 64, 85],
+            ),
+            (
+                FigurateFamily::CenteredPolygonal(6),
+                [1, 7, 19, 37, 61, 91, 127, 169],
+            ),
+            (FigurateFamily::TETRAHEDRAL, [1, 4, 10, 20, 35, 56, 84, 120]),
+            (
+                FigurateFamily::SQUARE_PYRAMIDAL,
+                [1, 5, 14, 30, 55, 91, 140, 204],
+            ),
+            (
+                FigurateFamily::Pyramidal(5),
+                [1, 6, 18, 40, 75, 126, 196, 288],
+            ),
+            (
+                FigurateFamily::Octahedral,
+                [1, 6, 19, 44, 85, 146, 231, 344],
+            ),
+            (FigurateFamily::Star, [1, 13, 37, 73, 121, 181, 253, 337]),
+        ];
+        for (family, values) in known {
+            let found: Vec<u64> = family.members().take(8).collect();
+            assert_eq!(found, values, "{family:?}");
+            for (n, value) in values.iter().enumerate() {
+                assert_eq!(family.index_of(*value), Some(n as u64 + 1), "{family:?}");
+            }
+            // the members and the membership test agree on everything up to the last value
+            for x in 0..=values[7] {
+                assert_eq!(family.contains(x), values.contains(&x), "{x} in {family:?}");
+            }
+        }
+        assert_eq!(
+            FigurateFamily::HEXAGONAL
+                .members()
+                .take(5)
+                .collect::<Vec<u64>>(),
+            [1, 6, 15, 28, 45]
+        );
+        assert_eq!(FigurateFamily::Star.next_member(14), Some(37));
+        assert_eq!(FigurateFamily::Star.next_member(0), Some(1));
+    }
+
+    #[test]
+    fn families_are_exact_near_overflow() {
+        use super::FigurateFamily;
+        for family in [
+            FigurateFamily::TRIANGULAR,
+            FigurateFamily::CenteredPolygonal(5),
+            FigurateFamily::TETRAHEDRAL,
+            FigurateFamily::Pyramidal(1000),
+            FigurateFamily::Octahedral,
+            FigurateFamily::Star,
+        ] {
+            let n = family.count_up_to(u64::MAX);
+            let largest = family.nth(n).unwrap();
+            assert_eq!(family.index_of(largest), Some(n), "{family:?}");
+            assert_eq!(family.nth(n + 1), None, "{family:?}");
+            assert_eq!(family.index_of(largest - 1), None, "{family:?}");
+            assert_eq!(family.next_member(largest + 1), None, "{family:?}");
+            assert_eq!(family.index_of(u64::MAX), None, "{family:?}");
+        }
+
+        // big enough that the intermediate products overflow u128 too
+        for family in [
+            FigurateFamily::Octahedral,
+            FigurateFamily::Star,
+            FigurateFamily::CenteredPolygonal(u64::MAX),
+            FigurateFamily::Pyramidal(u64::MAX),
+            FigurateFamily::Polygonal(u64::MAX),
+        ] {
+            assert_eq!(family.nth(1 << 63), None, "{family:?}");
+            assert_eq!(family.nth(u64::MAX), None, "{family:?}");
+        }
+    }
+
+    #[test]
+    fn intersections() {
+        use super::{FigurateFamily, figurate_intersection};
+        let found: Vec<u64> = figurate_intersection(&[
+            FigurateFamily::TRIANGULAR,
+            FigurateFamily::PENTAGONAL,
+            FigurateFamily::HEXAGONAL,
+        ])
+        .take(3)
+        .collect();
+        assert_eq!(found, [1, 40755, 1533776805]);
+
+        // square triangular numbers
+        let found: Vec<u64> =
+            figurate_intersection(&[FigurateFamily::SQUARE, FigurateFamily::TRIANGULAR])
+                .take(6)
+                .collect();
+        assert_eq!(found, [1, 36, 1225, 41616, 1413721, 48024900]);
+
+        // 1, 10, 120, 1540 and 7140 are the only triangular tetrahedral numbers
+        let found: Vec<u64> =
+            figurate_intersection(&[FigurateFamily::TETRAHEDRAL, FigurateFamily::TRIANGULAR])
+                .take(5)
+                .collect();
+        assert_eq!(found, [1, 10, 120, 1540, 7140]);
+        let found = FigurateFamily::TETRAHEDRAL
+            .members()
+            .take_while(|x| *x < 1 << 40)
+            .filter(|x| FigurateFamily::TRIANGULAR.contains(*x))
+            .count();
+        assert_eq!(found, 5);
+
+        // the only square pyramidal squares are 1 and 4900, the cannonball problem
+        let found: Vec<u64> = FigurateFamily::SQUARE_PYRAMIDAL
+            .members()
+            .take_while(|x| *x < 1 << 40)
+            .filter(|x| FigurateFamily::SQUARE.contains(*x))
+            .collect();
+        assert_eq!(found, [1, 4900]);
+
+        let stars: Vec<u64> = figurate_intersection(&[FigurateFamily::Star])
+            .take(3)
+            .collect();
+        assert_eq!(stars, [1, 13, 37]);
+    }
+
+    #[test]
+    fn digit_members() {
+        use super::FigurateFamily;
+        // T(45) = 1035 to T(140) = 9870
+        assert_eq!(
+            FigurateFamily::TRIANGULAR.members_with_digits(4).count(),
+            96
+        );
+        assert_eq!(FigurateFamily::SQUARE.members_with_digits(4).count(), 68);
+        assert_eq!(
+            FigurateFamily::TETRAHEDRAL
+                .members_with_digits(2)
+                .collect::<Vec<u64>>(),
+            [10, 20, 35, 56, 84]
+        );
+        assert_eq!(
+            FigurateFamily::Star
+                .members_with_digits(1)
+                .collect::<Vec<u64>>(),
+            [1]
+        );
+        assert_eq!(FigurateFamily::SQUARE.members_with_digits(21).count(), 0);
+        // u64 goes up to 20 digits
+        let first = FigurateFamily::SQUARE.members_with_digits(20).next();
+        assert_eq!(first, Some(3_162_277_661 * 3_162_277_661));
+    }
+
+    #[test]
+    fn overlap_cycles() {
+        use super::{FigurateFamily, FigurateNode, overlap_graph};
+        let families = [
+            FigurateFamily::TRIANGULAR,
+            FigurateFamily::SQUARE,
+            FigurateFamily::PENTAGONAL,
+        ];
+        let graph = overlap_graph(&families, 4, 2);
+        let node = |family, value| FigurateNode { family, value };
+        assert_eq!(
+            graph.cycles(3),
+            [vec![
+                node(FigurateFamily::TRIANGULAR, 8128),
+                node(FigurateFamily::PENTAGONAL, 2882),
+                node(FigurateFamily::SQUARE, 8281),
+            ]]
+        );
+        assert!(
+            graph
+                .successors(node(FigurateFamily::TRIANGULAR, 8128))
+                .contains(&node(FigurateFamily::PENTAGONAL, 2882))
+        );
+        assert_eq!(graph.nodes().count(), 96 + 68 + 56);
+
+        // every pair of families that overlaps both ways, checked against a brute force search
+        let pairs = graph.cycles(2);
+        let mut expected = Vec::new();
+        for a in graph.nodes() {
+            for b in graph.nodes() {
+                let a_first = families.iter().position(|f| *f == a.family);
+                let b_first = families.iter().position(|f| *f == b.family);
+                if a_first < b_first
+                    && a.value % 100 == b.value / 100
+                    && b.value % 100 == a.value / 100
+                {
+                    expected.push(vec![a, b]);
+                }
+            }
+        }
+        assert_eq!(pairs, expected);
+    }
 }
@@ -0,0 +1,203 @@
+use num_bigint::BigUint;
+use num_traits::{Num, PrimInt, Signed, Zero};
+
+/// Absolute value for any primitive integer. Overflows for the minimum of a signed type.
+fn prim_abs<I: PrimInt>(n: I) -> I {
+    if n < I::zero() { I::zero() - n } else { n }
+}
+
+/// Greatest common divisor by the Euclidean algorithm. The result is never negative, and
+/// gcd(0, 0) = 0.
+pub fn gcd<I: PrimInt>(a: I, b: I) -> I {
+    let (mut a, mut b) = (a, b);
+    while !b.is_zero() {
+        (a, b) = (b, a % b);
+    }
+    prim_abs(a)
+}
+
+/// Greatest common divisor by the Euclidean algorithm for any integer type, including BigInt and
+/// BigUint. The result is never negative.
+pub fn num_gcd<I: Clone + Num + PartialOrd>(a: I, b: I) -> I {
+    let (mut a, mut b) = (a, b);
+    while !b.is_zero() {
+        let remainder = a % b.clone();
+        (a, b) = (b, remainder);
+    }
+    if a < I::zero() { I::zero() - a } else { a }
+}
+
+/// Greatest common divisor by Stein's binary algorithm, which trades the divisions in gcd for
+/// shifts and subtractions
+#[allow(dead_code)]
+pub fn binary_gcd<I: PrimInt>(a: I, b: I) -> I {
+    let (mut a, mut b) = (prim_abs(a), prim_abs(b));
+    if a.is_zero() {
+        return b;
+    }
+    if b.is_zero() {
+        return a;
+    }
+
+    let shared_twos = (a | b).trailing_zeros() as usize;
+    a = a >> a.trailing_zeros() as usize;
+    loop {
+        // a is always odd here
+        b = b >> b.trailing_zeros() as usize;
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b = b - a;
+        if b.is_zero() {
+            return a << shared_twos;
+        }
+    }
+}
+
+/// Least common multiple. The result is never negative, and lcm(0, n) = 0.
+#[allow(dead_code)]
+pub fn lcm<I: PrimInt>(a: I, b: I) -> I {
+    if a.is_zero() || b.is_zero() {
+        return I::zero();
+    }
+    prim_abs(a / gcd(a, b) * b)
+}
+
+/// Divides both numbers by their gcd, and returns the gcd.
+/// If both numbers are 0, they're left alone and 0 is returned.
+pub fn reduce_by_gcd<I: PrimInt>(a: &mut I, b: &mut I) -> I {
+    let gcd = gcd(*a, *b);
+    if !gcd.is_zero() {
+        *a = *a / gcd;
+        *b = *b / gcd;
+    }
+    gcd
+}
+
+#[allow(dead_code)]
+pub fn big_gcd(a: &BigUint, b: &BigUint) -> BigUint {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while !b.is_zero() {
+        let remainder = &a % &b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+#[allow(dead_code)]
+pub fn big_binary_gcd(a: &BigUint, b: &BigUint) -> BigUint {
+    let (Some(a_twos), Some(b_twos)) = (a.trailing_zeros(), b.trailing_zeros()) else {
+        // one of them is 0
+        return a + b;
+    };
+
+    let mut a = a >> a_twos;
+    let mut b = b.clone();
+    loop {
+        b >>= b.trailing_zeros().unwrap_or(0);
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= &a;
+        if b.is_zero() {
+            return a << a_twos.min(b_twos);
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn big_lcm(a: &BigUint, b: &BigUint) -> BigUint {
+    if a.is_zero() || b.is_zero() {
+        return BigUint::zero();
+    }
+    a / big_gcd(a, b) * b
+}
+
+/// Finds (g, x, y) such that a*x + b*y = g = gcd(a, b), with g >= 0.
+/// This works for signed primitives and BigInt alike.
+pub fn extended_gcd<I: Clone + Signed>(a: I, b: I) -> (I, I, I) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (I::one(), I::zero());
+    let (mut old_y, mut y) = (I::zero(), I::one());
+    while !r.is_zero() {
+        let quotient = old_r.clone() / r.clone();
+        (old_r, r) = (r.clone(), old_r - quotient.clone() * r);
+        (old_x, x) = (x.clone(), old_x - quotient.clone() * x);
+        (old_y, y) = (y.clone(), old_y - quotient * y);
+    }
+
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint};
+
+    use super::{big_binary_gcd, big_gcd, big_lcm, binary_gcd, extended_gcd, gcd, lcm, num_gcd};
+
+    #[test]
+    fn gcd_matches_binary_gcd() {
+        for a in 0..100u32 {
+            for b in 0..100u32 {
+                let expected = gcd(a, b);
+                assert_eq!(binary_gcd(a, b), expected, "gcd({a}, {b})");
+                assert_eq!(
+                    big_gcd(&BigUint::from(a), &BigUint::from(b)),
+                    BigUint::from(expected)
+                );
+                assert_eq!(
+                    big_binary_gcd(&BigUint::from(a), &BigUint::from(b)),
+                    BigUint::from(expected)
+                );
+                assert_eq!(num_gcd(a, b), expected);
+                if expected != 0 {
+                    assert_eq!(a % expected, 0);
+                    assert_eq!(b % expected, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn signed_gcd() {
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(12i64, -18), 6);
+        assert_eq!(binary_gcd(-12i32, -18), 6);
+        assert_eq!(gcd(0i8, -5), 5);
+        assert_eq!(
+            num_gcd(BigInt::from(-12), BigInt::from(-18)),
+            BigInt::from(6)
+        );
+        assert_eq!(num_gcd(-7i64, 0), 7);
+    }
+
+    #[test]
+    fn lcms() {
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(-4i64, 6), 12);
+        assert_eq!(lcm(0u8, 6), 0);
+        assert_eq!(
+            big_lcm(&BigUint::from(u64::MAX), &BigUint::from(6u8)),
+            BigUint::from(u64::MAX) * 2u8
+        );
+    }
+
+    #[test]
+    fn extended_gcds() {
+        let (gcd, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(gcd, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(extended_gcd(-4i32, 6).0, 2);
+
+        let a = BigInt::from(u128::MAX);
+        let b = BigInt::from(u64::MAX) * BigInt::from(3);
+        let (gcd, x, y) = extended_gcd(a.clone(), b.clone());
+        assert_eq!(gcd, BigInt::from(u64::MAX));
+        assert_eq!(a * x + b * y, gcd);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::VecDeque,
+    ops::{Div, Sub},
+};
+
+use num_traits::{One, Zero};
+
+use crate::euler_tools::{
+    matrix::Matrix,
+    modular::{mod_add, mod_mul},
+};
+
+/// A linear recurrence a_n = c_1 a_{n-1} + c_2 a_{n-2} + ... + c_k a_{n-k}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct LinearRecurrence<T> {
+    /// c_1, ..., c_k
+    pub coefficients: Vec<T>,
+    /// a_0, ..., a_{k-1}
+    pub initial_terms: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T: Clone + Zero + One> LinearRecurrence<T> {
+    pub fn new(coefficients: Vec<T>, initial_terms: Vec<T>) -> Self {
+        assert_eq!(
+            coefficients.len(),
+            initial_terms.len(),
+            "a recurrence of order k needs k initial terms"
+        );
+        LinearRecurrence {
+            coefficients,
+            initial_terms,
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// The matrix that maps (a_{n+k-1}, ..., a_n) to (a_{n+k}, ..., a_{n+1})
+    pub fn companion_matrix(&self) -> Matrix<T> {
+        let k = self.order();
+        let mut companion = Matrix::zeros(k, k);
+        for (i, coefficient) in self.coefficients.iter().enumerate() {
+            companion[(0, i)] = coefficient.clone();
+        }
+        for i in 1..k {
+            companion[(i, i - 1)] = T::one();
+        }
+        companion
+    }
+
+    /// Finds a_n in O(k^3 log n) steps
+    pub fn nth(&self, n: u64) -> T {
+        let k = self.order();
+        if n < k as u64 {
+            return self.initial_terms[n as usize].clone();
+        }
+        if k == 0 {
+            return T::zero();
+        }
+
+        let latest_first: Vec<T> = self.initial_terms.iter().rev().cloned().collect();
+        let power = self.companion_matrix().pow(n - k as u64 + 1);
+        power.apply(&latest_first).swap_remove(0)
+    }
+
+    /// Iterates over a_0, a_1, a_2, ... one term at a time in O(k) steps each
+    pub fn terms(&self) -> impl Iterator<Item = T> + '_ {
+        let mut window: VecDeque<T> = self.initial_terms.iter().cloned().collect();
+        std::iter::from_fn(move || {
+            if window.is_empty() {
+                // the empty recurrence is all 0
+                return Some(T::zero());
+            }
+            let next = self
+                .coefficients
+                .iter()
+                .zip(window.iter().rev())
+                .fold(T::zero(), |sum, (c, a)| sum + c.clone() * a.clone());
+            window.push_back(next);
+            window.pop_front()
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl LinearRecurrence<u64> {
+    /// Finds a_n mod modulus in O(k^3 log n) steps
+    pub fn nth_mod(&self, n: u64, modulus: u64) -> u64 {
+        let k = self.order();
+        if n < k as u64 {
+            return self.initial_terms[n as usize] % modulus;
+        }
+        if k == 0 {
+            return 0;
+        }
+
+        let power = self.companion_matrix().pow_mod(n - k as u64 + 1, modulus);
+        self.initial_terms
+            .iter()
+            .rev()
+            .enumerate()
+            .fold(0, |sum, (i, a)| {
+                mod_add(sum, mod_mul(power[(0, i)], *a, modulus), modulus)
+            })
+    }
+}
+
+/// Finds the shortest linear recurrence that generates the given terms, using the
+/// Berlekamp–Massey algorithm. T needs to be a field, like ModInt with a prime modulus. With 2k
+/// terms, this recovers any recurrence of order k.
+#[allow(dead_code)]
+pub fn berlekamp_massey<T>(terms: &[T]) -> LinearRecurrence<T>
+where
+    T: Clone + Zero + One + Sub<Output = T> + Div<Output = T> + PartialEq,
+{
+    // connection polynomials: current[0] a_n + current[1] a_{n-1} + ... = 0
+    let mut current = vec![T::one()];
+    let mut previous = vec![T::one()];
+    let mut length = 0;
+    let mut shift = 1;
+    let mut previous_discrepancy = T::one();
+
+    for n in 0..terms.len() {
+        let discrepancy = (1..=length).fold(terms[n].clone(), |sum, i| {
+            sum + current[i].clone() * terms[n - i].clone()
+        });
+        if discrepancy.is_zero() {
+            shift += 1;
+            continue;
+        }
+
+        // current -= (discrepancy / previous_discrepancy) x^shift previous
+        let scale = discrepancy.clone() / previous_discrepancy.clone();
+        let old_current = current.clone();
+        if current.len() < previous.len() + shift {
+            current.resize(previous.len() + shift, T::zero());
+        }
+        for (i, coefficient) in previous.iter().enumerate() {
+            current[i + shift] = current[i + shift].clone() - scale.clone() * coefficient.clone();
+        }
+
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = old_current;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+
+    current.resize(length + 1, T::zero());
+    LinearRecurrence::new(
+        current[1..].iter().map(|c| T::zero() - c.clone()).collect(),
+        terms[..length].to_vec(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{LinearRecurrence, berlekamp_massey};
+    use crate::euler_tools::modular::ModInt;
+
+    type Mod = ModInt<998_244_353>;
+
+    fn mods(values: &[u64]) -> Vec<Mod> {
+        values.iter().map(|value| Mod::new(*value)).collect()
+    }
+
+    #[test]
+    fn nth_matches_terms() {
+        // tribonacci
+        let tribonacci = LinearRecurrence::new(vec![1u64, 1, 1], vec![0, 0, 1]);
+        let stepped: Vec<u64> = tribonacci.terms().take(40).collect();
+        for (n, term) in stepped.iter().enumerate() {
+            assert_eq!(tribonacci.nth(n as u64), *term, "T({n})");
+            assert_eq!(tribonacci.nth_mod(n as u64, 1000), term % 1000, "T({n})");
+        }
+        assert_eq!(stepped[..10], [0, 0, 1, 1, 2, 4, 7, 13, 24, 44]);
+
+        let big = LinearRecurrence::new(
+            vec![BigUint::from(0u8), BigUint::from(2u8)],
+            vec![BigUint::from(1u8), BigUint::from(3u8)],
+        );
+        assert_eq!(big.nth(201), BigUint::from(3u8) << 100);
+
+        let modular = LinearRecurrence::new(mods(&[1, 1, 1]), mods(&[0, 0, 1]));
+        let n = 1_000_000_000_000_000;
+        assert_eq!(modular.nth(n).value(), tribonacci.nth_mod(n, Mod::MODULUS));
+
+        assert_eq!(LinearRecurrence::<u64>::new(vec![], vec![]).nth(5), 0);
+    }
+
+    #[test]
+    fn recovers_recurrences() {
+        let tribonacci = LinearRecurrence::new(mods(&[1, 1, 1]), mods(&[0, 0, 1]));
+        let terms: Vec<Mod> = tribonacci.terms().take(20).collect();
+        assert_eq!(berlekamp_massey(&terms), tribonacci);
+
+        // a_n = 5 a_{n-1} - 2 a_{n-3} + 7 a_{n-4}, written mod p
+        let recurrence = LinearRecurrence::new(
+            vec![Mod::new(5), Mod::new(0), -Mod::new(2), Mod::new(7)],
+            mods(&[3, 1, 4, 1]),
+        );
+        let terms: Vec<Mod> = recurrence.terms().take(8).collect();
+        assert_eq!(berlekamp_massey(&terms), recurrence);
+
+        // sums of squares 0, 1, 5, 14, ... are a cubic, so they satisfy an order 4 recurrence
+        let terms: Vec<Mod> = (0..12u64)
+            .scan(0, |sum, n| {
+                *sum += n * n;
+                Some(Mod::new(*sum))
+            })
+            .collect();
+        let found = berlekamp_massey(&terms);
+        assert_eq!(
+            found.coefficients,
+            [Mod::new(4), -Mod::new(6), Mod::new(4), -Mod::new(1)]
+        );
+        assert_eq!(
+            found.nth(1000).value(),
+            1000 * 1001 * 2001 / 6 % Mod::MODULUS
+        );
+
+        assert_eq!(berlekamp_massey(&mods(&[0, 0, 0])).order(), 0);
+    }
+}
@@ -0,0 +1,231 @@
+use std::ops::{Index, IndexMut, Mul};
+
+use num_traits::{One, Zero};
+
+use crate::euler_tools::modular::{mod_add, mod_mul};
+
+/// A small dense matrix, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    rows: usize,
+    columns: usize,
+    entries: Vec<T>,
+}
+
+impl<T: Clone + Zero> Matrix<T> {
+    pub fn zeros(rows: usize, columns: usize) -> Self {
+        Matrix {
+            rows,
+            columns,
+            entries: vec![T::zero(); rows * columns],
+        }
+    }
+
+    /// Builds a matrix from its rows, which all need to be the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let row_count = rows.len();
+        let columns = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == columns),
+            "every row needs the same number of columns"
+        );
+        Matrix {
+            rows: row_count,
+            columns,
+            entries: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+impl<T: Clone + Zero + One> Matrix<T> {
+    /// Multiplies the matrix by a column vector
+    pub fn apply(&self, vector: &[T]) -> Vec<T> {
+        assert_eq!(
+            vector.len(),
+            self.columns,
+            "vector length must match the columns"
+        );
+        (0..self.rows)
+            .map(|row| {
+                self.entries[row * self.columns..(row + 1) * self.columns]
+                    .iter()
+                    .zip(vector)
+                    .fold(T::zero(), |sum, (a, b)| sum + a.clone() * b.clone())
+            })
+            .collect()
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut identity = Matrix::zeros(size, size);
+        for i in 0..size {
+            identity[(i, i)] = T::one();
+        }
+        identity
+    }
+
+    /// Raises a square matrix to a power by repeated squaring, in O(size^3 log(exponent))
+    pub fn pow(&self, exponent: u64) -> Self {
+        assert_eq!(self.rows, self.columns, "only square matrices have powers");
+        let mut result = Matrix::identity(self.rows);
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &Self::Output {
+        assert!(
+            row < self.rows && column < self.columns,
+            "index out of bounds"
+        );
+        &self.entries[row * self.columns + column]
+    }
+}
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            row < self.rows && column < self.columns,
+            "index out of bounds"
+        );
+        &mut self.entries[row * self.columns + column]
+    }
+}
+
+impl<T: Clone + Zero + Mul<Output = T>> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.columns, rhs.rows, "matrix dimensions don't match");
+        let mut product: Matrix<T> = Matrix::zeros(self.rows, rhs.columns);
+        for row in 0..self.rows {
+            for k in 0..self.columns {
+                let a = &self[(row, k)];
+                if a.is_zero() {
+                    continue;
+                }
+                for column in 0..rhs.columns {
+                    let term = a.clone() * rhs[(k, column)].clone();
+                    product[(row, column)] = product[(row, column)].clone() + term;
+                }
+            }
+        }
+        product
+    }
+}
+impl<T: Clone + Zero + Mul<Output = T>> Mul for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+// ModInt covers compile-time moduli, but plenty of problems only know theirs at runtime
+impl Matrix<u64> {
+    /// Multiplies two matrices with every entry reduced mod modulus
+    pub fn mul_mod(&self, rhs: &Self, modulus: u64) -> Self {
+        assert_eq!(self.columns, rhs.rows, "matrix dimensions don't match");
+        let mut product = Matrix::zeros(self.rows, rhs.columns);
+        for row in 0..self.rows {
+            for k in 0..self.columns {
+                let a = self[(row, k)];
+                for column in 0..rhs.columns {
+                    let term = mod_mul(a, rhs[(k, column)], modulus);
+                    product[(row, column)] = mod_add(product[(row, column)], term, modulus);
+                }
+            }
+        }
+        product
+    }
+
+    /// Raises a square matrix to a power mod modulus by repeated squaring
+    pub fn pow_mod(&self, exponent: u64, modulus: u64) -> Self {
+        assert_eq!(self.rows, self.columns, "only square matrices have powers");
+        let mut result = Matrix::identity(self.rows);
+        for i in 0..self.rows {
+            result[(i, i)] %= modulus;
+        }
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul_mod(&base, modulus);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul_mod(&base, modulus);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::Matrix;
+    use crate::euler_tools::modular::ModInt;
+
+    #[test]
+    fn multiplication() {
+        let a = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let b = Matrix::from_rows(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+        let product = &a * &b;
+        assert_eq!(
+            product,
+            Matrix::from_rows(vec![vec![58, 64], vec![139, 154]])
+        );
+        assert_eq!((product.rows(), product.columns()), (2, 2));
+        assert_eq!(a.apply(&[1, 0, -1]), [-2, -2]);
+        assert_eq!(&Matrix::identity(2) * &a, a);
+    }
+
+    #[test]
+    fn powers() {
+        let fibonacci = Matrix::from_rows(vec![vec![1u64, 1], vec![1, 0]]);
+        assert_eq!(fibonacci.pow(0), Matrix::identity(2));
+        assert_eq!(fibonacci.pow(10)[(0, 1)], 55);
+        assert_eq!(fibonacci.pow(92)[(0, 1)], 7_540_113_804_746_346_429);
+
+        let big = Matrix::from_rows(vec![
+            vec![BigUint::from(1u8), BigUint::from(1u8)],
+            vec![BigUint::from(1u8), BigUint::from(0u8)],
+        ]);
+        assert_eq!(big.pow(300)[(0, 1)].to_string().len(), 63);
+
+        let modular: Matrix<ModInt<1_000_000_007>> = Matrix::from_rows(vec![
+            vec![ModInt::new(1), ModInt::new(1)],
+            vec![ModInt::new(1), ModInt::new(0)],
+        ]);
+        let runtime = fibonacci.pow_mod(1_000_000_000_000_000, 1_000_000_007);
+        assert_eq!(
+            modular.pow(1_000_000_000_000_000)[(0, 1)].value(),
+            runtime[(0, 1)]
+        );
+
+        let rotation = Matrix::from_rows(vec![vec![0i64, -1], vec![1, 0]]);
+        assert_eq!(rotation.pow(4), Matrix::identity(2));
+        assert_eq!(fibonacci.pow_mod(5, 1), Matrix::zeros(2, 2));
+    }
+}
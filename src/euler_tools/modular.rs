@@ -0,0 +1,380 @@
+pub mod multiplicative_group;
+pub mod quadratic_residues;
+
+use std::{
+    fmt::Display,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, ConstOne, ConstZero, Inv, One, Pow, Zero};
+
+use crate::euler_tools::gcd::extended_gcd;
+
+/// An integer modulo M. Multiplication goes through u128, so any M up to u64::MAX is safe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct ModInt<const M: u64> {
+    value: u64,
+}
+
+#[allow(dead_code)]
+impl<const M: u64> ModInt<M> {
+    pub const MODULUS: u64 = M;
+
+    pub const fn new(value: u64) -> Self {
+        const { assert!(M > 0, "modulus must be positive") };
+        ModInt { value: value % M }
+    }
+
+    pub const fn from_signed(value: i64) -> Self {
+        const { assert!(M > 0, "modulus must be positive") };
+        ModInt {
+            value: (value as i128).rem_euclid(M as i128) as u64,
+        }
+    }
+
+    /// The canonical representative, in [0, M)
+    pub const fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn pow(self, exponent: u64) -> Self {
+        ModInt {
+            value: mod_pow(self.value, exponent, M),
+        }
+    }
+
+    /// The multiplicative inverse, if the value is coprime to M
+    pub fn inverse(self) -> Option<Self> {
+        Some(ModInt {
+            value: mod_inverse(self.value, M)?,
+        })
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        ModInt::new(value)
+    }
+}
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(value: i64) -> Self {
+        ModInt::from_signed(value)
+    }
+}
+
+impl<const M: u64> Display for ModInt<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (sum, overflowed) = self.value.overflowing_add(rhs.value);
+        ModInt {
+            value: if overflowed || sum >= M {
+                sum.wrapping_sub(M)
+            } else {
+                sum
+            },
+        }
+    }
+}
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        ModInt {
+            value: if self.value >= rhs.value {
+                self.value - rhs.value
+            } else {
+                M - (rhs.value - self.value)
+            },
+        }
+    }
+}
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        ModInt {
+            value: mod_mul(self.value, rhs.value, M),
+        }
+    }
+}
+impl<const M: u64> Div for ModInt<M> {
+    type Output = ModInt<M>;
+
+    /// Panics if rhs isn't invertible mod M
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inv()
+    }
+}
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn neg(self) -> Self::Output {
+        ModInt::ZERO - self
+    }
+}
+impl<const M: u64> Inv for ModInt<M> {
+    type Output = ModInt<M>;
+
+    /// Panics if the value isn't invertible mod M
+    fn inv(self) -> Self::Output {
+        self.inverse()
+            .expect("value must be coprime to the modulus to be inverted")
+    }
+}
+impl<const M: u64> Pow<u64> for ModInt<M> {
+    type Output = ModInt<M>;
+
+    fn pow(self, exponent: u64) -> Self::Output {
+        ModInt::pow(self, exponent)
+    }
+}
+
+macro_rules! mod_int_assign_impl {
+    ($(($assign_trait:ident, $assign_fn:ident, $op_fn:ident)),*) => { $(
+        impl<const M: u64> $assign_trait for ModInt<M> {
+            fn $assign_fn(&mut self, rhs: Self) {
+                *self = (*self).$op_fn(rhs);
+            }
+        }
+    )* };
+}
+mod_int_assign_impl!(
+    (AddAssign, add_assign, add),
+    (SubAssign, sub_assign, sub),
+    (MulAssign, mul_assign, mul),
+    (DivAssign, div_assign, div)
+);
+
+// Modular arithmetic never overflows, so the checked operations always succeed. This lets
+// ModInt plug into things like fibonacci_iterator, which stop when a checked operation fails.
+macro_rules! mod_int_checked_impl {
+    ($(($checked_trait:ident, $checked_fn:ident, $op_fn:ident)),*) => { $(
+        impl<const M: u64> $checked_trait for ModInt<M> {
+            fn $checked_fn(&self, rhs: &Self) -> Option<Self> {
+                Some((*self).$op_fn(*rhs))
+            }
+        }
+    )* };
+}
+mod_int_checked_impl!(
+    (CheckedAdd, checked_add, add),
+    (CheckedSub, checked_sub, sub),
+    (CheckedMul, checked_mul, mul)
+);
+
+impl<const M: u64> Zero for ModInt<M> {
+    fn zero() -> Self {
+        ModInt::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+impl<const M: u64> ConstZero for ModInt<M> {
+    const ZERO: Self = ModInt { value: 0 };
+}
+impl<const M: u64> One for ModInt<M> {
+    fn one() -> Self {
+        ModInt::ONE
+    }
+}
+impl<const M: u64> ConstOne for ModInt<M> {
+    const ONE: Self = ModInt { value: 1 % M };
+}
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt::ZERO, Add::add)
+    }
+}
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt::ONE, Mul::mul)
+    }
+}
+
+/// Computes a + b mod modulus without overflowing
+pub fn mod_add(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 + b as u128) % modulus as u128) as u64
+}
+
+/// Computes a - b mod modulus without overflowing
+pub fn mod_sub(a: u64, b: u64, modulus: u64) -> u64 {
+    mod_add(a % modulus, modulus - b % modulus, modulus)
+}
+
+/// Computes a * b mod modulus without overflowing
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// Computes base^exponent mod modulus by repeated squaring
+pub fn mod_pow(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Finds x such that a*x ≡ 1 (mod modulus), or None if a and the modulus aren't coprime
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    let (gcd, x, _) = extended_gcd(a as i128, modulus as i128);
+    if gcd != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus as i128) as u64)
+}
+
+/// Solves the system x ≡ residue (mod modulus) for each (residue, modulus) pair. The moduli don't
+/// need to be coprime.
+///
+/// Returns (x, lcm of the moduli) with 0 <= x < lcm, or None if the congruences are inconsistent
+/// or the lcm doesn't fit in a u64.
+#[allow(dead_code)]
+pub fn chinese_remainder(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let mut solution: i128 = 0;
+    let mut combined_modulus: i128 = 1;
+    for (residue, modulus) in congruences {
+        assert_ne!(*modulus, 0, "modulus must be positive");
+        let (residue, modulus) = (*residue as i128, *modulus as i128);
+
+        // solution + combined_modulus * t ≡ residue (mod modulus)
+        let (gcd, inverse, _) = extended_gcd(combined_modulus, modulus);
+        let difference = residue - solution;
+        if difference % gcd != 0 {
+            return None;
+        }
+        let reduced_modulus = modulus / gcd;
+        // both factors are below 2^64, so their product fits in a u128
+        let t = ((difference / gcd).rem_euclid(reduced_modulus) as u128
+            * inverse.rem_euclid(reduced_modulus) as u128
+            % reduced_modulus as u128) as i128;
+
+        let next_modulus = combined_modulus.checked_mul(reduced_modulus)?;
+        if next_modulus > u64::MAX as i128 {
+            return None;
+        }
+        solution = (solution + combined_modulus * t).rem_euclid(next_modulus);
+        combined_modulus = next_modulus;
+    }
+    Some((solution as u64, combined_modulus as u64))
+}
+
+/// Turns a sorted list of prime factors into (prime, exponent) pairs
+fn group_factors(factors: &[u64]) -> Vec<(u64, u32)> {
+    let mut grouped: Vec<(u64, u32)> = Vec::new();
+    for factor in factors {
+        match grouped.last_mut() {
+            Some((p, k)) if p == factor => *k += 1,
+            _ => grouped.push((*factor, 1)),
+        }
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::Inv;
+
+    use super::{ModInt, chinese_remainder, mod_inverse, mod_pow};
+    use crate::euler_tools::fibonacci_iterator;
+
+    type Mod7 = ModInt<7>;
+    const BIG_PRIME: u64 = 18_446_744_073_709_551_557; // largest prime below 2^64
+    type ModBig = ModInt<BIG_PRIME>;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Mod7::new(5) + Mod7::new(4), Mod7::new(2));
+        assert_eq!(Mod7::new(2) - Mod7::new(4), Mod7::new(5));
+        assert_eq!(Mod7::new(3) * Mod7::new(5), Mod7::new(1));
+        assert_eq!(Mod7::new(1) / Mod7::new(3), Mod7::new(5));
+        assert_eq!(-Mod7::new(3), Mod7::new(4));
+        assert_eq!(Mod7::from_signed(-10), Mod7::new(4));
+        assert_eq!(Mod7::new(3).inv(), Mod7::new(5));
+        assert_eq!(Mod7::new(3).pow(6u64), Mod7::new(1));
+        assert_eq!(ModInt::<6>::new(2).inverse(), None);
+
+        let mut n = Mod7::new(6);
+        n += Mod7::new(6);
+        n *= Mod7::new(2);
+        n -= Mod7::new(2);
+        n /= Mod7::new(2);
+        assert_eq!(n.value(), 4);
+    }
+
+    #[test]
+    fn no_overflow_near_u64_max() {
+        let a = ModBig::new(BIG_PRIME - 1);
+        assert_eq!(a + a, ModBig::new(BIG_PRIME - 2));
+        assert_eq!(a * a, ModBig::new(1));
+        assert_eq!(a.pow(BIG_PRIME - 1), ModBig::new(1));
+        assert_eq!(ModBig::from_signed(-1), a);
+        assert_eq!(a * a.inv(), ModBig::new(1));
+    }
+
+    #[test]
+    fn sums_and_fibonacci() {
+        let sum: ModInt<1000> = (1..=100u64).map(ModInt::new).sum();
+        assert_eq!(sum.value(), 50);
+        let product: ModInt<1000> = (1..=10u64).map(ModInt::new).product();
+        assert_eq!(product.value(), 800);
+
+        // the Pisano period mod 10 is 60
+        let fib: Vec<ModInt<10>> = fibonacci_iterator().take(62).collect();
+        assert_eq!(fib[60], ModInt::new(0));
+        assert_eq!(fib[61], ModInt::new(1));
+    }
+
+    #[test]
+    fn free_functions() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(4, 10), None);
+    }
+
+    #[test]
+    fn crt() {
+        assert_eq!(
+            chinese_remainder(&[(2, 3), (3, 5), (2, 7)]),
+            Some((23, 105))
+        );
+        // non-coprime moduli
+        assert_eq!(chinese_remainder(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(chinese_remainder(&[(1, 4), (2, 6)]), None);
+        assert_eq!(chinese_remainder(&[]), Some((0, 1)));
+        // the lcm is just below 2^64, so intermediate products need the full u128 range
+        let big_congruences = [
+            (4_000_000_000, 4_000_000_007),
+            (3_999_999_999, 4_000_000_009),
+        ];
+        let (solution, modulus) = chinese_remainder(&big_congruences).unwrap();
+        assert_eq!(modulus, 4_000_000_007 * 4_000_000_009);
+        for (residue, modulus) in big_congruences {
+            assert_eq!(solution % modulus, residue);
+        }
+        assert_eq!(
+            chinese_remainder(&[(1, BIG_PRIME), (0, 2)]),
+            None,
+            "lcm doesn't fit in a u64"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use integer_sqrt::IntegerSquareRoot;
+
+use crate::euler_tools::{
+    gcd::{gcd, lcm},
+    modular::{group_factors, mod_mul, mod_pow},
+    prime_finder::Primes,
+};
+
+/// Euler's totient of n, from its factorization
+#[allow(dead_code)]
+fn totient(n: u64, primes: &Primes) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    group_factors(&primes.prime_factorize(n))
+        .iter()
+        .filter(|(p, _)| *p > 1)
+        .map(|(p, k)| p.pow(k - 1) * (p - 1))
+        .product()
+}
+
+/// The Carmichael function: the smallest m such that a^m ≡ 1 (mod n) for every a coprime to n.
+/// The primes need to be able to factor n.
+pub fn carmichael_lambda(n: u64, primes: &Primes) -> u64 {
+    assert_ne!(n, 0, "n must be positive");
+    group_factors(&primes.prime_factorize(n))
+        .iter()
+        .filter(|(p, _)| *p > 1)
+        .map(|(p, k)| match (p, k) {
+            // powers of 2 above 4 aren't cyclic, so they only get half of the totient
+            (2, k) if *k >= 3 => 1 << (k - 2),
+            (p, k) => p.pow(k - 1) * (p - 1),
+        })
+        .fold(1, lcm)
+}
+
+/// Finds the smallest k > 0 with a^k ≡ 1 (mod n), or None if a isn't coprime to n.
+/// The primes need to be able to factor n.
+pub fn multiplicative_order(a: u64, n: u64, primes: &Primes) -> Option<u64> {
+    assert_ne!(n, 0, "n must be positive");
+    if gcd(a, n) != 1 {
+        return None;
+    }
+
+    // the order divides lambda(n), so strip out every prime factor we can
+    let mut order = carmichael_lambda(n, primes);
+    for q in primes.unique_prime_factorize(order) {
+        if q < 2 {
+            continue;
+        }
+        while order.is_multiple_of(q) && mod_pow(a, order / q, n) == 1 % n {
+            order /= q;
+        }
+    }
+    Some(order)
+}
+
+/// Finds the smallest primitive root mod n, i.e. a generator of the multiplicative group.
+/// Only 1, 2, 4, p^k and 2p^k for odd primes p have one; anything else returns None.
+/// The primes need to be able to factor n.
+#[allow(dead_code)]
+pub fn primitive_root(n: u64, primes: &Primes) -> Option<u64> {
+    assert_ne!(n, 0, "n must be positive");
+    if n <= 2 {
+        return Some(n - 1);
+    }
+    if n == 4 {
+        return Some(3);
+    }
+
+    let odd_part = if n % 4 == 2 { n / 2 } else { n };
+    let odd_factors = primes.unique_prime_factorize(odd_part);
+    if odd_part % 2 == 0 || odd_factors.len() != 1 {
+        return None;
+    }
+
+    let phi = totient(n, primes);
+    let phi_factors = primes.unique_prime_factorize(phi);
+    (2..n).find(|g| gcd(*g, n) == 1 && phi_factors.iter().all(|q| mod_pow(*g, phi / q, n) != 1))
+}
+
+/// Finds the smallest x >= 0 with base^x ≡ target (mod modulus) using baby-step giant-step, in
+/// O(sqrt(modulus)) time and space. The base doesn't need to be coprime to the modulus.
+#[allow(dead_code)]
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    assert_ne!(modulus, 0, "modulus must be positive");
+    let mut modulus = modulus;
+    let base = base % modulus;
+    let mut target = target % modulus;
+
+    // Peel off the factors the base shares with the modulus. Afterwards we're solving
+    // coefficient * base^x ≡ target with the base coprime to the modulus.
+    let mut steps = 0;
+    let mut coefficient = 1 % modulus;
+    loop {
+        let shared = gcd(base, modulus);
+        if shared == 1 {
+            break;
+        }
+        if target == coefficient {
+            return Some(steps);
+        }
+        if !target.is_multiple_of(shared) {
+            return None;
+        }
+        target /= shared;
+        modulus /= shared;
+        steps += 1;
+        coefficient = mod_mul(coefficient, base / shared % modulus, modulus);
+    }
+    if target == coefficient {
+        return Some(steps);
+    }
+    let base = base % modulus;
+
+    let giant_step_size = modulus.integer_sqrt() + 1;
+
+    // baby steps: target * base^j -> j, keeping the largest j so the answer is the smallest
+    let mut baby_steps: HashMap<u64, u64> = HashMap::with_capacity(giant_step_size as usize);
+    let mut baby_step = target;
+    for j in 0..giant_step_size {
+        baby_steps.insert(baby_step, j);
+        baby_step = mod_mul(baby_step, base, modulus);
+    }
+
+    let giant_step = mod_pow(base, giant_step_size, modulus);
+    let mut current = coefficient;
+    for i in 1..=giant_step_size {
+        current = mod_mul(current, giant_step, modulus);
+        if let Some(j) = baby_steps.get(&current) {
+            return Some(i * giant_step_size - j + steps);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{carmichael_lambda, discrete_log, multiplicative_order, primitive_root};
+    use crate::euler_tools::{gcd::gcd, modular::mod_pow, prime_finder::Primes};
+
+    fn brute_force_order(a: u64, n: u64) -> Option<u64> {
+        if gcd(a, n) != 1 {
+            return None;
+        }
+        (1..=n).find(|k| mod_pow(a, *k, n) == 1 % n)
+    }
+
+    #[test]
+    fn orders_and_lambda() {
+        let primes = Primes::find_primes(100);
+        for n in 1..300 {
+            let mut expected_lambda = 1;
+            for a in 0..n {
+                let order = multiplicative_order(a, n, &primes);
+                assert_eq!(order, brute_force_order(a, n), "order of {a} mod {n}");
+                if let Some(order) = order {
+                    expected_lambda = expected_lambda.max(order);
+                }
+            }
+            assert_eq!(
+                carmichael_lambda(n, &primes),
+                expected_lambda,
+                "lambda({n})"
+            );
+        }
+    }
+
+    #[test]
+    fn primitive_roots() {
+        let primes = Primes::find_primes(100);
+        for n in 1..300u64 {
+            let phi = (1..=n).filter(|a| gcd(*a, n) == 1).count() as u64;
+            let expected = (0..n).find(|g| brute_force_order(*g, n) == Some(phi));
+            assert_eq!(
+                primitive_root(n, &primes),
+                expected,
+                "primitive root of {n}"
+            );
+        }
+        let primes = Primes::find_primes(1 << 16);
+        assert_eq!(primitive_root(998_244_353, &primes), Some(3));
+        assert_eq!(primitive_root(1_000_000_007, &primes), Some(5));
+    }
+
+    #[test]
+    fn discrete_logs() {
+        for modulus in 1..60 {
+            for base in 0..modulus {
+                for target in 0..modulus {
+                    let expected = (0..2 * modulus).find(|x| mod_pow(base, *x, modulus) == target);
+                    assert_eq!(
+                        discrete_log(base, target, modulus),
+                        expected,
+                        "log base {base} of {target} mod {modulus}"
+                    );
+                }
+            }
+        }
+
+        let p = 1_000_000_007;
+        let x = 123_456_789;
+        assert_eq!(discrete_log(5, mod_pow(5, x, p), p), Some(x));
+    }
+}
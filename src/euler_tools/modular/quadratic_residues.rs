@@ -0,0 +1,347 @@
+use crate::euler_tools::{
+    modular::{chinese_remainder, group_factors, mod_add, mod_inverse, mod_mul, mod_pow, mod_sub},
+    prime_finder::Primes,
+};
+
+/// Computes the Legendre symbol (a / p) for an odd prime p: 0 if p divides a, 1 if a is a
+/// quadratic residue mod p, and -1 otherwise
+pub fn legendre_symbol(a: u64, p: u64) -> i8 {
+    debug_assert!(p > 2 && p % 2 == 1, "p must be an odd prime");
+    // Euler's criterion
+    match mod_pow(a, (p - 1) / 2, p) {
+        0 => 0,
+        1 => 1,
+        _ => -1,
+    }
+}
+
+/// Computes the Jacobi symbol (a / n) for any odd positive n, using quadratic reciprocity.
+/// When n is prime this is the Legendre symbol, but for composite n, 1 doesn't guarantee that a is
+/// a quadratic residue.
+#[allow(dead_code)]
+pub fn jacobi_symbol(a: u64, n: u64) -> i8 {
+    assert!(n % 2 == 1, "n must be odd and positive");
+    let mut a = a % n;
+    let mut n = n;
+    let mut symbol = 1;
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+            // (2 / n) = -1 exactly when n ≡ 3, 5 (mod 8)
+            if n % 8 == 3 || n % 8 == 5 {
+                symbol = -symbol;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            symbol = -symbol;
+        }
+        a %= n;
+    }
+    if n == 1 { symbol } else { 0 }
+}
+
+/// Finds some x with x^2 ≡ a (mod p) for an odd prime p using the Tonelli–Shanks algorithm.
+/// The other root is p - x.
+pub fn tonelli_shanks(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a == 0 {
+        return Some(0);
+    }
+    if legendre_symbol(a, p) != 1 {
+        return None;
+    }
+
+    // p - 1 = q * 2^s with q odd
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    if s == 1 {
+        // p ≡ 3 (mod 4) has a direct formula
+        return Some(mod_pow(a, (p + 1) / 4, p));
+    }
+
+    let non_residue = (2..p)
+        .find(|z| legendre_symbol(*z, p) == -1)
+        .expect("half of the nonzero values mod p are non-residues");
+
+    let mut m = s;
+    let mut c = mod_pow(non_residue, q, p);
+    let mut t = mod_pow(a, q, p);
+    let mut root = mod_pow(a, q.div_ceil(2), p);
+    while t != 1 {
+        // find the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t_power = t;
+        while t_power != 1 {
+            t_power = mod_mul(t_power, t_power, p);
+            i += 1;
+        }
+
+        let b = mod_pow(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mod_mul(b, b, p);
+        t = mod_mul(t, c, p);
+        root = mod_mul(root, b, p);
+    }
+    Some(root)
+}
+
+/// Finds some x with x^2 ≡ a (mod p) for an odd prime p using Cipolla's algorithm, which works in
+/// the field extension F_p(sqrt(t^2 - a)). The other root is p - x.
+#[allow(dead_code)]
+pub fn cipolla(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a == 0 {
+        return Some(0);
+    }
+    if legendre_symbol(a, p) != 1 {
+        return None;
+    }
+
+    let (t, omega_squared) = (0..p)
+        .map(|t| (t, mod_sub(mod_mul(t, t, p), a, p)))
+        .find(|(_, omega_squared)| legendre_symbol(*omega_squared, p) == -1)
+        .expect("half of the values of t^2 - a are non-residues");
+
+    // (x0 + x1 w) * (y0 + y1 w) where w^2 = omega_squared
+    let multiply = |(x0, x1): (u64, u64), (y0, y1): (u64, u64)| {
+        (
+            mod_add(
+                mod_mul(x0, y0, p),
+                mod_mul(mod_mul(x1, y1, p), omega_squared, p),
+                p,
+            ),
+            mod_add(mod_mul(x0, y1, p), mod_mul(x1, y0, p), p),
+        )
+    };
+
+    // (t + w)^((p+1)/2) lands back in F_p
+    let mut result = (1, 0);
+    let mut base = (t, 1);
+    let mut exponent = p.div_ceil(2);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply(result, base);
+        }
+        base = multiply(base, base);
+        exponent >>= 1;
+    }
+    debug_assert_eq!(result.1, 0);
+    Some(result.0)
+}
+
+/// Finds some x with x^2 ≡ a (mod p) for any prime p
+#[allow(dead_code)]
+pub fn sqrt_mod_prime(a: u64, p: u64) -> Option<u64> {
+    if p == 2 {
+        Some(a % 2)
+    } else {
+        tonelli_shanks(a, p)
+    }
+}
+
+/// Finds every x in [0, p^k) with x^2 ≡ a (mod p^k), in increasing order
+pub fn sqrt_mod_prime_power(a: u64, p: u64, k: u32) -> Vec<u64> {
+    let modulus = p.pow(k);
+    let a = a % modulus;
+
+    let mut roots: Vec<u64> = if a == 0 {
+        // x^2 ≡ 0 exactly when p^ceil(k/2) divides x
+        let step = p.pow(k.div_ceil(2));
+        (0..modulus / step).map(|j| j * step).collect()
+    } else {
+        let mut valuation = 0;
+        let mut unit = a;
+        while unit.is_multiple_of(p) {
+            unit /= p;
+            valuation += 1;
+        }
+        if valuation % 2 == 1 {
+            return Vec::new();
+        }
+
+        // x = p^(v/2) y with y^2 ≡ a / p^v (mod p^(k-v)), which fixes x mod p^(k - v/2)
+        let half_power = p.pow(valuation / 2);
+        let unit_modulus = p.pow(k - valuation);
+        let fixed_modulus = unit_modulus * half_power;
+        sqrt_unit_mod_prime_power(unit, p, k - valuation)
+            .into_iter()
+            .flat_map(|y| {
+                (0..half_power).map(move |t| half_power * y % modulus + t * fixed_modulus)
+            })
+            .collect()
+    };
+    roots.sort_unstable();
+    roots.dedup();
+    roots
+}
+
+/// Square roots of a mod p^k when p doesn't divide a
+fn sqrt_unit_mod_prime_power(a: u64, p: u64, k: u32) -> Vec<u64> {
+    if k == 0 {
+        return vec![0];
+    }
+    if p == 2 {
+        // lift the roots one bit at a time. There are at most 4 of them, so just try both options
+        let mut roots = vec![1];
+        for bits in 2..=k {
+            let modulus = 1 << bits;
+            roots = roots
+                .iter()
+                .flat_map(|root| [*root, root + (modulus >> 1)])
+                .filter(|root| mod_mul(*root, *root, modulus) == a % modulus)
+                .collect();
+        }
+        return roots;
+    }
+
+    let Some(mut root) = tonelli_shanks(a, p) else {
+        return Vec::new();
+    };
+    // Hensel lifting: r -> r - (r^2 - a) / (2r)
+    let mut modulus = p;
+    for _ in 1..k {
+        modulus *= p;
+        let excess = mod_sub(mod_mul(root, root, modulus), a, modulus);
+        let inverse =
+            mod_inverse(mod_add(root, root, modulus), modulus).expect("2r is coprime to p");
+        root = mod_sub(root, mod_mul(excess, inverse, modulus), modulus);
+    }
+    vec![root, modulus - root]
+}
+
+/// Finds every x in [0, n) with x^2 ≡ a (mod n), in increasing order. The primes need to be able
+/// to factor n.
+#[allow(dead_code)]
+pub fn sqrt_mod(a: u64, n: u64, primes: &Primes) -> Vec<u64> {
+    assert_ne!(n, 0, "modulus must be positive");
+    if n == 1 {
+        return vec![0];
+    }
+
+    let factors = primes.prime_factorize(n);
+    let mut combined: Vec<(u64, u64)> = vec![(0, 1)];
+    for (p, k) in group_factors(&factors) {
+        let prime_power = p.pow(k);
+        let roots = sqrt_mod_prime_power(a, p, k);
+        if roots.is_empty() {
+            return Vec::new();
+        }
+        combined = combined
+            .iter()
+            .flat_map(|(solution, modulus)| {
+                roots.iter().map(move |root| {
+                    chinese_remainder(&[(*solution, *modulus), (*root, prime_power)])
+                        .expect("prime powers are coprime, and their product is n")
+                })
+            })
+            .collect();
+    }
+
+    let mut roots: Vec<u64> = combined.into_iter().map(|(root, _)| root).collect();
+    roots.sort_unstable();
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cipolla, jacobi_symbol, legendre_symbol, sqrt_mod, sqrt_mod_prime_power, tonelli_shanks,
+    };
+    use crate::euler_tools::prime_finder::Primes;
+
+    fn brute_force_roots(a: u64, n: u64) -> Vec<u64> {
+        (0..n).filter(|x| x * x % n == a % n).collect()
+    }
+
+    #[test]
+    fn legendre_and_jacobi() {
+        let primes = Primes::find_primes(100);
+        for p in primes.prime_iterator().skip(1) {
+            let p = *p as u64;
+            for a in 0..p {
+                let expected = if a == 0 {
+                    0
+                } else if brute_force_roots(a, p).is_empty() {
+                    -1
+                } else {
+                    1
+                };
+                assert_eq!(legendre_symbol(a, p), expected, "({a} / {p})");
+                assert_eq!(jacobi_symbol(a, p), expected, "({a} / {p})");
+            }
+        }
+
+        // the Jacobi symbol is multiplicative in n
+        for a in 0..45 {
+            assert_eq!(
+                jacobi_symbol(a, 45),
+                jacobi_symbol(a, 3) * jacobi_symbol(a, 3) * jacobi_symbol(a, 5)
+            );
+        }
+        assert_eq!(jacobi_symbol(2, 15), 1, "2 is a non-residue mod 15 anyway");
+        assert!(brute_force_roots(2, 15).is_empty());
+    }
+
+    #[test]
+    fn prime_square_roots() {
+        let primes = Primes::find_primes(200);
+        for p in primes.prime_iterator().skip(1) {
+            let p = *p as u64;
+            for a in 0..p {
+                let expected = brute_force_roots(a, p);
+                for root in [tonelli_shanks(a, p), cipolla(a, p)] {
+                    match root {
+                        Some(root) => assert!(expected.contains(&root), "sqrt({a}) mod {p}"),
+                        None => assert!(expected.is_empty(), "sqrt({a}) mod {p}"),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large_prime_square_roots() {
+        // 2^64 - 59 ≡ 5 (mod 8), and 998244353 = 119 * 2^23 + 1 needs many Tonelli–Shanks steps
+        for p in [18_446_744_073_709_551_557u64, 998_244_353] {
+            for a in [2, 3, 5, 1_000_000_007, p - 1] {
+                let a = a % p;
+                let a_squared = (a as u128 * a as u128 % p as u128) as u64;
+                for root in [tonelli_shanks(a_squared, p), cipolla(a_squared, p)] {
+                    let root = root.unwrap();
+                    assert!(root == a || root == p - a, "sqrt({a}^2) mod {p}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prime_power_square_roots() {
+        for (p, max_k) in [(2u64, 7), (3, 5), (5, 3), (7, 3)] {
+            for k in 1..=max_k {
+                let modulus = p.pow(k);
+                for a in 0..modulus {
+                    assert_eq!(
+                        sqrt_mod_prime_power(a, p, k),
+                        brute_force_roots(a, modulus),
+                        "sqrt({a}) mod {p}^{k}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn composite_square_roots() {
+        let primes = Primes::find_primes(100);
+        for n in 1..300 {
+            for a in 0..n {
+                assert_eq!(
+                    sqrt_mod(a, n, &primes),
+                    brute_force_roots(a, n),
+                    "sqrt({a}) mod {n}"
+                );
+            }
+        }
+    }
+}
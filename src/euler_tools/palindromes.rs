@@ -0,0 +1,368 @@
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::euler_tools::DigitIterator;
+
+/// Iterates over the palindromes in a radix that are at most a limit, building each one from its
+/// first half instead of testing every number. 0 counts as a palindrome.
+pub struct Palindromes {
+    radix: u128,
+    limit: u128,
+    ascending: bool,
+    /// the number of digits in the palindromes being built, or 0 once there are none left
+    length: u32,
+    /// the first (length + 1) / 2 digits of the next palindrome
+    half: u128,
+}
+
+impl Palindromes {
+    /// The smallest and largest halves for the current length
+    fn half_range(&self) -> (u128, u128) {
+        let half_length = self.length.div_ceil(2);
+        let low = match self.length {
+            1 => 0,
+            _ => self.radix.pow(half_length - 1),
+        };
+        let high = self
+            .radix
+            .checked_pow(half_length)
+            .map_or(u128::MAX, |end| end - 1);
+        (low, high)
+    }
+
+    /// Mirrors the half onto the end of itself, sharing the middle digit when the length is odd
+    fn build(&self) -> Option<u128> {
+        let mut mirrored = match self.length % 2 {
+            1 => self.half / self.radix,
+            _ => self.half,
+        };
+        let mut value = self.half;
+        while mirrored > 0 {
+            value = value.checked_mul(self.radix)? + mirrored % self.radix;
+            mirrored /= self.radix;
+        }
+        Some(value)
+    }
+
+    fn step(&mut self) {
+        let (low, high) = self.half_range();
+        if self.ascending {
+            if self.half == high {
+                self.length += 1;
+                self.half = self.half_range().0;
+            } else {
+                self.half += 1;
+            }
+        } else if self.half == low {
+            self.length -= 1;
+            if self.length > 0 {
+                self.half = self.half_range().1;
+            }
+        } else {
+            self.half -= 1;
+        }
+    }
+}
+
+impl Iterator for Palindromes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.length > 0 {
+            let value = self.build().filter(|value| *value <= self.limit);
+            match value {
+                Some(value) => {
+                    self.step();
+                    return Some(value as u64);
+                }
+                // everything after this is bigger too
+                None if self.ascending => self.length = 0,
+                // only the first half of the limit itself can be too big
+                None => self.step(),
+            }
+        }
+        None
+    }
+}
+
+/// The palindromes in the given radix from 0 up to limit, in increasing order
+#[allow(dead_code)]
+pub fn palindromes(radix: u64, limit: u64) -> Palindromes {
+    assert!(radix >= 2, "the radix must be at least 2");
+    Palindromes {
+        radix: radix as u128,
+        limit: limit as u128,
+        ascending: true,
+        length: 1,
+        half: 0,
+    }
+}
+
+/// The palindromes in the given radix from limit down to 0, in decreasing order
+#[allow(dead_code)]
+pub fn palindromes_descending(radix: u64, limit: u64) -> Palindromes {
+    assert!(radix >= 2, "the radix must be at least 2");
+    let length = DigitIterator::<u64>::new_radix(limit, radix).count().max(1) as u32;
+    let mut palindromes = Palindromes {
+        radix: radix as u128,
+        limit: limit as u128,
+        ascending: false,
+        length,
+        half: 0,
+    };
+    // start from the first half of the limit, which is the biggest half that could still fit
+    palindromes.half = limit as u128 / palindromes.radix.pow(length / 2);
+    palindromes
+}
+
+/// Negative numbers are treated like their absolute values, and keep their sign when reversed
+pub trait Palindrome: Sized {
+    /// Whether the number reads the same backwards in the given radix, without building the
+    /// reversed number
+    fn is_palindrome_radix(&self, radix: u32) -> bool;
+    /// The number with its digits in the given radix reversed, dropping any leading zeros this
+    /// makes. Panics if that overflows.
+    #[allow(dead_code)]
+    fn reverse_digits(&self, radix: u32) -> Self {
+        self.checked_reverse_digits(radix)
+            .expect("the reversed number overflowed")
+    }
+    /// The number with its digits in the given radix reversed, or None if that overflows
+    fn checked_reverse_digits(&self, radix: u32) -> Option<Self>;
+}
+
+macro_rules! palindrome_impl {
+    ($($prim_type:ty),*) => { $(
+        impl Palindrome for $prim_type {
+            fn is_palindrome_radix(&self, radix: u32) -> bool {
+                assert!(radix >= 2, "the radix must be at least 2");
+                // a radix too big for the type is bigger than the number, which is then one digit
+                let Some(radix) = <$prim_type>::try_from(radix).ok() else {
+                    return true;
+                };
+                if *self != 0 && *self % radix == 0 {
+                    return false;
+                }
+                // reverse the back half until it catches up with the front half, so it can't
+                // overflow
+                let mut front = *self;
+                let mut back: $prim_type = 0;
+                while front > back {
+                    back = back * radix + front % radix;
+                    front /= radix;
+                }
+                front == back || front == back / radix
+            }
+
+            fn checked_reverse_digits(&self, radix: u32) -> Option<Self> {
+                assert!(radix >= 2, "the radix must be at least 2");
+                let Some(radix) = <$prim_type>::try_from(radix).ok() else {
+                    return Some(*self);
+                };
+                let mut remaining = *self;
+                let mut reversed: $prim_type = 0;
+                while remaining > 0 {
+                    reversed = reversed.checked_mul(radix)?.checked_add(remaining % radix)?;
+                    remaining /= radix;
+                }
+                Some(reversed)
+            }
+        }
+    )* };
+}
+palindrome_impl!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! signed_palindrome_impl {
+    ($($prim_type:ty),*) => { $(
+        impl Palindrome for $prim_type {
+            fn is_palindrome_radix(&self, radix: u32) -> bool {
+                self.unsigned_abs().is_palindrome_radix(radix)
+            }
+
+            fn checked_reverse_digits(&self, radix: u32) -> Option<Self> {
+                let reversed = self.unsigned_abs().checked_reverse_digits(radix)?;
+                let reversed = <$prim_type>::try_from(reversed).ok()?;
+                Some(if *self < 0 { -reversed } else { reversed })
+            }
+        }
+    )* };
+}
+signed_palindrome_impl!(i8, i16, i32, i64, i128, isize);
+
+impl Palindrome for BigUint {
+    fn is_palindrome_radix(&self, radix: u32) -> bool {
+        assert!(radix >= 2, "the radix must be at least 2");
+        if radix.is_power_of_two() {
+            // each digit is a run of bits, so they can be compared where they are
+            let digit_bits = radix.trailing_zeros() as u64;
+            let length = self.bits().div_ceil(digit_bits);
+            let same_digit = |i: u64, j: u64| {
+                (0..digit_bits)
+                    .all(|bit| self.bit(i * digit_bits + bit) == self.bit(j * digit_bits + bit))
+            };
+            (0..length / 2).all(|i| same_digit(i, length - 1 - i))
+        } else if radix <= 256 {
+            let digits = self.to_radix_le(radix);
+            digits.iter().eq(digits.iter().rev())
+        } else {
+            // to_radix_le only goes up to 256, so these digits are split off one at a time
+            let digits: Vec<u32> =
+                DigitIterator::new_radix(self.clone(), BigUint::from(radix)).collect();
+            digits.iter().eq(digits.iter().rev())
+        }
+    }
+
+    fn checked_reverse_digits(&self, radix: u32) -> Option<Self> {
+        assert!(radix >= 2, "the radix must be at least 2");
+        if self.is_zero() {
+            return Some(BigUint::zero());
+        }
+        // the least significant digit first, read as the most significant first
+        if radix <= 256 {
+            return BigUint::from_radix_be(&self.to_radix_le(radix), radix);
+        }
+        let digits: Vec<u32> =
+            DigitIterator::new_radix(self.clone(), BigUint::from(radix)).collect();
+        Some(DigitIterator::<BigUint>::combine_digits_big_endian_radix(
+            &digits, radix,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{Palindrome, palindromes, palindromes_descending};
+
+    fn brute_force(radix: u32, limit: u64) -> Vec<u64> {
+        (0..=limit)
+            .filter(|n| {
+                let digits: Vec<u64> =
+                    crate::euler_tools::DigitIterator::<u64>::new_radix(*n, radix as u64).collect();
+                digits.iter().eq(digits.iter().rev())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generated_in_order() {
+        assert_eq!(
+            palindromes(10, 121).collect::<Vec<u64>>(),
+            [
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 11, 22, 33, 44, 55, 66, 77, 88, 99, 101, 111, 121
+            ]
+        );
+        assert_eq!(
+            palindromes_descending(2, 16).collect::<Vec<u64>>(),
+            [15, 9, 7, 5, 3, 1, 0]
+        );
+        for radix in 2..=12 {
+            for limit in [0, 1, 5, 100, 121, 1000, 4095, 4096, 10_000] {
+                let expected = brute_force(radix, limit);
+                let found: Vec<u64> = palindromes(radix as u64, limit).collect();
+                assert_eq!(found, expected, "up to {limit} in base {radix}");
+                let mut found: Vec<u64> = palindromes_descending(radix as u64, limit).collect();
+                found.reverse();
+                assert_eq!(found, expected, "down from {limit} in base {radix}");
+            }
+        }
+
+        assert_eq!(
+            palindromes_descending(10, u64::MAX).next(),
+            Some(18446744066044764481)
+        );
+        assert_eq!(
+            palindromes_descending(u64::MAX, u64::MAX).take(2).count(),
+            2
+        );
+        // 10^18 has 19 digits, but the next palindrome down only has 18
+        assert_eq!(
+            palindromes_descending(10, 1_000_000_000_000_000_000).next(),
+            Some(999_999_999_999_999_999)
+        );
+    }
+
+    #[test]
+    fn tests_and_reversals() {
+        for radix in 2..=12u32 {
+            let expected = brute_force(radix, 3000);
+            let found: Vec<u64> = (0..=3000u64)
+                .filter(|n| n.is_palindrome_radix(radix))
+                .collect();
+            assert_eq!(found, expected, "base {radix}");
+            let big: Vec<u64> = (0..=3000u64)
+                .filter(|n| BigUint::from(*n).is_palindrome_radix(radix))
+                .collect();
+            assert_eq!(big, expected, "base {radix}");
+        }
+        assert!(u64::MAX.is_palindrome_radix(2));
+        assert!(255u8.is_palindrome_radix(2));
+        assert!(!254u8.is_palindrome_radix(2));
+        assert!(0u8.is_palindrome_radix(10));
+        assert!(!10u8.is_palindrome_radix(10));
+
+        assert_eq!(1230u32.reverse_digits(10), 321);
+        assert_eq!(0b1101u32.reverse_digits(2), 0b1011);
+        assert_eq!(0u32.reverse_digits(7), 0);
+        assert_eq!(4_000_000_009u32.checked_reverse_digits(10), None);
+        assert_eq!(199u8.checked_reverse_digits(10), None);
+        assert_eq!(152u8.checked_reverse_digits(10), Some(251));
+        let big: BigUint = "123456789012345678901234567890".parse().unwrap();
+        let reversed: BigUint = "98765432109876543210987654321".parse().unwrap();
+        assert_eq!(big.reverse_digits(10), reversed);
+        assert_eq!(BigUint::from(0u32).reverse_digits(10), BigUint::from(0u32));
+    }
+
+    #[test]
+    fn big_radixes() {
+        // a radix that doesn't fit in the type makes every number a single digit
+        assert!(200u8.is_palindrome_radix(256));
+        assert!(5u8.is_palindrome_radix(300));
+        assert_eq!(200u8.checked_reverse_digits(256), Some(200));
+        assert_eq!(5u8.reverse_digits(300), 5);
+        assert!(u16::MAX.is_palindrome_radix(1 << 16));
+        assert_eq!(u16::MAX.reverse_digits(70_000), u16::MAX);
+
+        // but a radix that fits still splits the number up
+        assert!(257u16.is_palindrome_radix(256));
+        assert!(!258u16.is_palindrome_radix(256));
+        assert_eq!(258u16.reverse_digits(256), 513);
+        assert!(!300u16.is_palindrome_radix(300));
+        assert_eq!(300u16.reverse_digits(300), 1);
+        assert!(301u16.is_palindrome_radix(300));
+
+        // BigUint takes a different route for powers of 2, radixes up to 256 and the rest
+        for radix in [16u32, 32, 255, 256, 257, 1000, 1 << 20] {
+            for n in (0..5000u64).chain([u64::MAX, 1 << 40, (1 << 40) + 1]) {
+                let big = BigUint::from(n);
+                assert_eq!(
+                    big.is_palindrome_radix(radix),
+                    n.is_palindrome_radix(radix),
+                    "{n} in base {radix}"
+                );
+                if let Some(reversed) = n.checked_reverse_digits(radix) {
+                    assert_eq!(
+                        big.reverse_digits(radix),
+                        BigUint::from(reversed),
+                        "{n} reversed in base {radix}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn signed() {
+        assert!((-121i32).is_palindrome_radix(10));
+        assert!(!(-123i32).is_palindrome_radix(10));
+        assert!(!i8::MIN.is_palindrome_radix(2));
+        assert!((-127i8).is_palindrome_radix(2));
+        assert!((-5i8).is_palindrome_radix(1000));
+        assert_eq!((-123i32).reverse_digits(10), -321);
+        assert_eq!((-120i8).reverse_digits(10), -21);
+        assert_eq!(120i8.reverse_digits(10), 21);
+        assert_eq!(i8::MIN.checked_reverse_digits(10), None);
+        assert_eq!(i64::MIN.checked_reverse_digits(2), Some(-1));
+    }
+}
@@ -0,0 +1,226 @@
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use crate::euler_tools::{
+    figurate_numbers::Pentagon,
+    modular::{mod_add, mod_sub},
+};
+
+/// The generalized pentagonal numbers k(3k-1)/2 for k = 1, -1, 2, -2, ... that are at most n,
+/// paired with whether their term is added or subtracted in Euler's recurrence
+fn signed_pentagonal_numbers(n: usize) -> impl Iterator<Item = (usize, bool)> {
+    (1i64..)
+        .flat_map(|k| [k, -k])
+        .map(|k| (Pentagon::pentagon(k) as usize, k % 2 != 0))
+        .take_while(move |(pentagon, _)| *pentagon <= n)
+}
+
+/// Finds p(0), ..., p(limit) by Euler's pentagonal number theorem,
+/// p(n) = p(n-1) + p(n-2) - p(n-5) - p(n-7) + p(n-12) + ..., in O(limit^1.5) steps
+pub fn partition_numbers(limit: usize) -> Vec<BigUint> {
+    let mut partitions: Vec<BigUint> = Vec::with_capacity(limit + 1);
+    partitions.push(BigUint::one());
+    for n in 1..=limit {
+        // the subtracted terms never outweigh the added ones, so BigUint won't underflow
+        let (added, subtracted) = signed_pentagonal_numbers(n).fold(
+            (BigUint::zero(), BigUint::zero()),
+            |(added, subtracted), (pentagon, is_added)| {
+                if is_added {
+                    (added + &partitions[n - pentagon], subtracted)
+                } else {
+                    (added, subtracted + &partitions[n - pentagon])
+                }
+            },
+        );
+        partitions.push(added - subtracted);
+    }
+    partitions
+}
+
+/// The number of ways to write n as a sum of positive integers, ignoring order
+pub fn partition_number(n: usize) -> BigUint {
+    partition_numbers(n).swap_remove(n)
+}
+
+/// Finds p(0), ..., p(limit) mod modulus by Euler's pentagonal number theorem
+pub fn partition_numbers_mod(limit: usize, modulus: u64) -> Vec<u64> {
+    let mut partitions: Vec<u64> = Vec::with_capacity(limit + 1);
+    partitions.push(1 % modulus);
+    for n in 1..=limit {
+        let p = signed_pentagonal_numbers(n).fold(0, |p, (pentagon, is_added)| {
+            if is_added {
+                mod_add(p, partitions[n - pentagon], modulus)
+            } else {
+                mod_sub(p, partitions[n - pentagon], modulus)
+            }
+        });
+        partitions.push(p);
+    }
+    partitions
+}
+
+/// Counts the ways to make every total up to limit out of the given parts, each of which can be
+/// used any number of times, like making change from a set of coins
+pub fn restricted_partitions<T: Clone + Zero + One>(parts: &[usize], limit: usize) -> Vec<T> {
+    let mut ways = vec![T::zero(); limit + 1];
+    ways[0] = T::one();
+    for part in parts.iter().filter(|part| **part > 0) {
+        for total in *part..=limit {
+            ways[total] = ways[total].clone() + ways[total - part].clone();
+        }
+    }
+    ways
+}
+
+/// Counts the ways to make every total up to limit out of the given parts, each of which can be
+/// used at most once
+pub fn distinct_restricted_partitions<T: Clone + Zero + One>(
+    parts: &[usize],
+    limit: usize,
+) -> Vec<T> {
+    let mut ways = vec![T::zero(); limit + 1];
+    ways[0] = T::one();
+    for part in parts.iter().filter(|part| **part > 0) {
+        // going downwards keeps each part from being used twice
+        for total in (*part..=limit).rev() {
+            ways[total] = ways[total].clone() + ways[total - part].clone();
+        }
+    }
+    ways
+}
+
+/// Counts the partitions of every total up to limit into distinct parts, q(0), ..., q(limit)
+#[allow(dead_code)]
+pub fn distinct_partitions<T: Clone + Zero + One>(limit: usize) -> Vec<T> {
+    let parts: Vec<usize> = (1..=limit).collect();
+    distinct_restricted_partitions(&parts, limit)
+}
+
+/// Iterates over every partition of n with the parts in decreasing order, starting from [n] and
+/// ending with [1, 1, ..., 1]
+pub struct Partitions {
+    next: Option<Vec<u64>>,
+}
+
+impl Iterator for Partitions {
+    type Item = Vec<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+
+        // break the last part bigger than 1 into pieces that are each one smaller
+        let mut next = current.clone();
+        let mut remaining = 0;
+        while next.last() == Some(&1) {
+            next.pop();
+            remaining += 1;
+        }
+        if let Some(last) = next.pop() {
+            let piece = last - 1;
+            remaining += last;
+            while remaining >= piece {
+                next.push(piece);
+                remaining -= piece;
+            }
+            if remaining > 0 {
+                next.push(remaining);
+            }
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+#[allow(dead_code)]
+pub fn partitions(n: u64) -> Partitions {
+    Partitions {
+        next: Some(if n == 0 { Vec::new() } else { vec![n] }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{
+        distinct_partitions, distinct_restricted_partitions, partition_number, partition_numbers,
+        partition_numbers_mod, partitions, restricted_partitions,
+    };
+
+    #[test]
+    fn partition_counts() {
+        let expected = [
+            1u32, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42, 56, 77, 101, 135, 176,
+        ];
+        let found = partition_numbers(expected.len() - 1);
+        assert_eq!(found, expected.map(BigUint::from));
+
+        assert_eq!(partition_number(100), BigUint::from(190_569_292u32));
+        assert_eq!(
+            partition_number(1000).to_string(),
+            "24061467864032622473692149727991"
+        );
+
+        let modular = partition_numbers_mod(1000, 1_000_007);
+        for (p, expected) in modular.iter().zip(partition_numbers(1000)) {
+            assert_eq!(BigUint::from(*p), expected % 1_000_007u32);
+        }
+        assert_eq!(partition_numbers_mod(3, 1), [0, 0, 0, 0]);
+
+        // every partition is counted exactly once by the unrestricted version
+        let all_parts: Vec<usize> = (1..=30).collect();
+        let restricted: Vec<BigUint> = restricted_partitions(&all_parts, 30);
+        assert_eq!(restricted, partition_numbers(30));
+    }
+
+    #[test]
+    fn restricted_counts() {
+        // making change for £2
+        let coins = [1, 2, 5, 10, 20, 50, 100, 200];
+        assert_eq!(restricted_partitions::<u64>(&coins, 200)[200], 73682);
+        assert_eq!(restricted_partitions::<u64>(&[2, 3, 5, 7], 10)[10], 5);
+        assert_eq!(restricted_partitions::<u64>(&[], 3), [1, 0, 0, 0]);
+
+        // partitions into distinct parts are equinumerous with partitions into odd parts
+        let odd_parts: Vec<usize> = (1..=60).step_by(2).collect();
+        let odd: Vec<u64> = restricted_partitions(&odd_parts, 60);
+        assert_eq!(distinct_partitions::<u64>(60), odd);
+        assert_eq!(
+            distinct_partitions::<u64>(10)[..],
+            [1, 1, 1, 2, 2, 3, 4, 5, 6, 8, 10]
+        );
+        assert_eq!(
+            distinct_restricted_partitions::<u64>(&[1, 2, 3], 6),
+            [1, 1, 1, 2, 1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn enumeration() {
+        let found: Vec<Vec<u64>> = partitions(5).collect();
+        let expected: Vec<Vec<u64>> = vec![
+            vec![5],
+            vec![4, 1],
+            vec![3, 2],
+            vec![3, 1, 1],
+            vec![2, 2, 1],
+            vec![2, 1, 1, 1],
+            vec![1, 1, 1, 1, 1],
+        ];
+        assert_eq!(found, expected);
+
+        let counts = partition_numbers(20);
+        for n in 0..=20 {
+            let all: Vec<Vec<u64>> = partitions(n).collect();
+            assert_eq!(
+                BigUint::from(all.len()),
+                counts[n as usize],
+                "partitions of {n}"
+            );
+            for partition in all {
+                assert_eq!(partition.iter().sum::<u64>(), n);
+                assert!(partition.is_sorted_by(|a, b| a >= b));
+            }
+        }
+    }
+}
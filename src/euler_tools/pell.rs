@@ -0,0 +1,318 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap, HashSet},
+};
+
+use integer_sqrt::IntegerSquareRoot;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{CheckedAdd, CheckedMul, One, Signed, Zero};
+
+use crate::euler_tools::continued_fraction::{convergents, sqrt_expansion};
+
+/// The fundamental solutions of x^2 - D y^2 = 1 and, when it's solvable, x^2 - D y^2 = -1
+struct PellUnits {
+    positive: (BigInt, BigInt),
+    negative: Option<(BigInt, BigInt)>,
+}
+
+/// Reads the fundamental solutions off the continued fraction of sqrt(D). Returns None when D is
+/// a perfect square.
+fn pell_units(d: u64) -> Option<PellUnits> {
+    let expansion = sqrt_expansion(d);
+    let period = expansion.period.len();
+    if period == 0 {
+        return None;
+    }
+
+    // the convergent just before the end of the first period satisfies p^2 - D q^2 = (-1)^period
+    let (p, q): (BigInt, BigInt) = convergents(expansion.terms())
+        .nth(period - 1)
+        .expect("the expansion is infinite")
+        .into_parts();
+    if period.is_multiple_of(2) {
+        Some(PellUnits {
+            positive: (p, q),
+            negative: None,
+        })
+    } else {
+        // squaring a solution of the negative equation gives one of the positive equation
+        let positive = (&p * &p + d * &q * &q, 2 * &p * &q);
+        Some(PellUnits {
+            positive,
+            negative: Some((p, q)),
+        })
+    }
+}
+
+/// Finds the smallest solution of x^2 - D y^2 = 1 with y > 0, or None if D is a perfect square
+pub fn fundamental_solution(d: u64) -> Option<(BigUint, BigUint)> {
+    pell_units(d).map(|units| into_biguint_pair(units.positive))
+}
+
+/// Finds the smallest positive solution of x^2 - D y^2 = -1, if there is one. That happens exactly
+/// when the continued fraction of sqrt(D) has an odd period.
+#[allow(dead_code)]
+pub fn negative_fundamental_solution(d: u64) -> Option<(BigUint, BigUint)> {
+    pell_units(d)?.negative.map(into_biguint_pair)
+}
+
+fn into_biguint_pair((x, y): (BigInt, BigInt)) -> (BigUint, BigUint) {
+    (x.into_parts().1, y.into_parts().1)
+}
+
+/// floor((p + sqrt(D)) / q) for a non-square D with floor(sqrt(D)) = root
+fn floor_quadratic(p: i128, q: i128, root: i128) -> i128 {
+    if q > 0 {
+        (p + root).div_euclid(q)
+    } else {
+        // sqrt(D) is irrational, so the quotient is never an integer
+        -((p + root).div_euclid(-q) + 1)
+    }
+}
+
+/// One solution of x^2 - D y^2 = n with gcd(x, y) = 1 from every solution class, using the
+/// Lagrange-Matthews-Mollin algorithm. The signs of the solutions are arbitrary.
+fn primitive_class_representatives(d: u64, n: i64, units: &PellUnits) -> Vec<(BigInt, BigInt)> {
+    let root = d.integer_sqrt() as i128;
+    let d = d as i128;
+    let modulus = n.unsigned_abs() as i128;
+
+    let mut representatives = Vec::new();
+    // every class corresponds to some -|n|/2 < z <= |n|/2 with z^2 ≡ D (mod |n|)
+    for z in (-(modulus - 1) / 2)..=(modulus / 2) {
+        if (z * z - d).rem_euclid(modulus) != 0 {
+            continue;
+        }
+
+        // expand (z + sqrt(D)) / |n| until some Q_i = ±1, where g^2 - D b^2 = (-1)^i Q_i |n|
+        let (mut p, mut q) = (z, modulus);
+        let (mut g_prev, mut g) = (BigInt::from(-z), BigInt::from(modulus));
+        let (mut b_prev, mut b) = (BigInt::one(), BigInt::zero());
+        let mut seen = HashSet::new();
+        let mut i = 0;
+        while seen.insert((p, q)) {
+            if q.abs() == 1 {
+                let norm_sign = if i % 2 == 0 { q } else { -q };
+                if (norm_sign > 0) == (n > 0) {
+                    representatives.push((g, b));
+                } else if let Some((t, u)) = &units.negative {
+                    // wrong sign, so multiply through by a solution of the negative equation
+                    representatives.push((&g * t + d * &b * u, &g * u + &b * t));
+                }
+                break;
+            }
+
+            let a = floor_quadratic(p, q, root);
+            (g_prev, g) = (g.clone(), a * &g + g_prev);
+            (b_prev, b) = (b.clone(), a * &b + b_prev);
+            p = a * q - p;
+            q = (d - p * p) / q;
+            i += 1;
+        }
+    }
+    representatives
+}
+
+/// Moves a solution along its class to the smallest solution with x, y >= 0
+fn smallest_nonnegative(
+    (x, y): (BigInt, BigInt),
+    d: u64,
+    n: i64,
+    (unit_x, unit_y): &(BigInt, BigInt),
+) -> (BigInt, BigInt) {
+    // make x + y sqrt(D) positive, so that multiplying by the unit pushes both x and y upwards
+    let mixed_signs = x.is_negative() != y.is_negative() && !x.is_zero() && !y.is_zero();
+    let (mut x, mut y) =
+        if (!x.is_positive() && !y.is_positive()) || (mixed_signs && x.is_negative() == (n > 0)) {
+            (-x, -y)
+        } else {
+            (x, y)
+        };
+
+    while x.is_negative() || y.is_negative() {
+        (x, y) = (&x * unit_x + d * &y * unit_y, &x * unit_y + &y * unit_x);
+    }
+    loop {
+        let previous = (&x * unit_x - d * &y * unit_y, &y * unit_x - &x * unit_y);
+        if previous.0.is_negative() || previous.1.is_negative() {
+            return (x, y);
+        }
+        (x, y) = previous;
+    }
+}
+
+/// Every solution of x^2 - D y^2 = n when D is the perfect square k^2, from the factorizations
+/// (x - ky)(x + ky) = n
+fn square_solutions(k: u64, n: i64) -> BTreeSet<(BigUint, BigUint)> {
+    let magnitude = n.unsigned_abs();
+    (1..=magnitude.integer_sqrt())
+        .filter(|a| magnitude.is_multiple_of(*a))
+        .map(|a| (a, magnitude / a))
+        .filter(|(a, b)| (a + b).is_multiple_of(2))
+        .map(|(a, b)| {
+            if n > 0 {
+                ((a + b) / 2, (b - a) / 2)
+            } else {
+                ((b - a) / 2, (a + b) / 2)
+            }
+        })
+        .filter(|(_, ky)| ky.is_multiple_of(k))
+        .map(|(x, ky)| (BigUint::from(x), BigUint::from(ky / k)))
+        .collect()
+}
+
+/// Iterates over every solution of x^2 - D y^2 = n with x, y >= 0, in increasing order.
+/// Solutions are stepped along with checked arithmetic, so the iterator ends once they no longer
+/// fit in T.
+#[allow(dead_code)]
+pub struct PellSolutions<T> {
+    d: T,
+    unit: Option<(T, T)>,
+    pending: BinaryHeap<Reverse<(T, T)>>,
+}
+
+impl<T: Clone + Ord + CheckedAdd + CheckedMul> Iterator for PellSolutions<T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((x, y)) = self.pending.pop()?;
+        if let Some((unit_x, unit_y)) = &self.unit {
+            // (x + y sqrt(D)) (unit_x + unit_y sqrt(D))
+            let next_x = x.checked_mul(unit_x).and_then(|xx| {
+                self.d
+                    .checked_mul(&y)
+                    .and_then(|dy| dy.checked_mul(unit_y))
+                    .and_then(|dyy| xx.checked_add(&dyy))
+            });
+            let next_y = x
+                .checked_mul(unit_y)
+                .and_then(|xy| y.checked_mul(unit_x).and_then(|yx| xy.checked_add(&yx)));
+            if let (Some(next_x), Some(next_y)) = (next_x, next_y) {
+                self.pending.push(Reverse((next_x, next_y)));
+            }
+        }
+        Some((x, y))
+    }
+}
+
+/// Solves the generalized Pell equation x^2 - D y^2 = n for positive D and nonzero n.
+/// The iterator is infinite (up to overflow) unless D is a perfect square or there are no
+/// solutions at all.
+#[allow(dead_code)]
+pub fn pell_solutions<T>(d: u64, n: i64) -> PellSolutions<T>
+where
+    T: Clone + Ord + CheckedAdd + CheckedMul + TryFrom<BigUint>,
+{
+    assert_ne!(d, 0, "D must be positive");
+    assert_ne!(n, 0, "n must be nonzero");
+
+    let (starts, unit) = match pell_units(d) {
+        None => (square_solutions(d.integer_sqrt(), n), None),
+        Some(units) => {
+            // solutions with gcd(x, y) = f come from primitive solutions of n / f^2
+            let magnitude = n.unsigned_abs();
+            let starts: BTreeSet<(BigUint, BigUint)> = (1..=magnitude.integer_sqrt())
+                .filter(|f| magnitude.is_multiple_of(f * f))
+                .flat_map(|f| {
+                    let reduced_n = n / (f * f) as i64;
+                    primitive_class_representatives(d, reduced_n, &units)
+                        .into_iter()
+                        .map(move |(x, y)| (x * f, y * f))
+                        .map(|solution| smallest_nonnegative(solution, d, n, &units.positive))
+                        .collect::<Vec<_>>()
+                })
+                .map(into_biguint_pair)
+                .collect();
+            (starts, Some(into_biguint_pair(units.positive)))
+        }
+    };
+
+    let convert = |(x, y): (BigUint, BigUint)| Some((T::try_from(x).ok()?, T::try_from(y).ok()?));
+    PellSolutions {
+        d: T::try_from(BigUint::from(d))
+            .ok()
+            .expect("D needs to fit in T"),
+        unit: unit.and_then(convert),
+        pending: starts
+            .into_iter()
+            .filter_map(convert)
+            .map(Reverse)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use integer_sqrt::IntegerSquareRoot;
+    use num_bigint::BigUint;
+
+    use super::{fundamental_solution, negative_fundamental_solution, pell_solutions};
+
+    fn big_pair(x: u64, y: u64) -> Option<(BigUint, BigUint)> {
+        Some((BigUint::from(x), BigUint::from(y)))
+    }
+
+    #[test]
+    fn fundamental_solutions() {
+        assert_eq!(fundamental_solution(2), big_pair(3, 2));
+        assert_eq!(fundamental_solution(3), big_pair(2, 1));
+        assert_eq!(fundamental_solution(4), None);
+        assert_eq!(fundamental_solution(5), big_pair(9, 4));
+        assert_eq!(fundamental_solution(6), big_pair(5, 2));
+        assert_eq!(fundamental_solution(7), big_pair(8, 3));
+        assert_eq!(fundamental_solution(13), big_pair(649, 180));
+        assert_eq!(
+            fundamental_solution(61),
+            big_pair(1_766_319_049, 226_153_980)
+        );
+
+        assert_eq!(negative_fundamental_solution(2), big_pair(1, 1));
+        assert_eq!(negative_fundamental_solution(3), None);
+        assert_eq!(negative_fundamental_solution(13), big_pair(18, 5));
+        assert_eq!(negative_fundamental_solution(61), big_pair(29_718, 3_805));
+        assert_eq!(negative_fundamental_solution(9), None);
+    }
+
+    #[test]
+    fn generalized_solutions_match_brute_force() {
+        let max_y = 300u64;
+        for d in 1..40u64 {
+            for n in -40..=40i64 {
+                if n == 0 {
+                    continue;
+                }
+                let expected: Vec<(u64, u64)> = (0..=max_y)
+                    .filter_map(|y| {
+                        let x_squared = n.checked_add((d * y * y) as i64)?;
+                        let x = u64::try_from(x_squared).ok()?.integer_sqrt();
+                        (x * x == x_squared as u64).then_some((x, y))
+                    })
+                    .collect();
+                let found: Vec<(u64, u64)> = pell_solutions::<u64>(d, n)
+                    .take_while(|(_, y)| *y <= max_y)
+                    .collect();
+                assert_eq!(found, expected, "x^2 - {d} y^2 = {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn big_solutions() {
+        // the Pell numbers: x^2 - 2y^2 = ±1 alternates along the convergents of sqrt(2)
+        let negative: Vec<(BigUint, BigUint)> = pell_solutions(2, -1).take(4).collect();
+        assert_eq!(
+            negative,
+            [(1u64, 1u64), (7, 5), (41, 29), (239, 169)]
+                .map(|(x, y)| (BigUint::from(x), BigUint::from(y)))
+        );
+
+        let (x, y) = pell_solutions::<BigUint>(661, 1).nth(5).unwrap();
+        assert_eq!(
+            &x * &x - BigUint::from(661u32) * &y * &y,
+            BigUint::from(1u32)
+        );
+
+        // the u64 iterator stops at overflow instead of wrapping
+        assert!(pell_solutions::<u64>(61, 1).count() < 4);
+    }
+}
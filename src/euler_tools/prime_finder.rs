@@ -1,12 +1,116 @@
-use std::{cmp::max, collections::VecDeque};
+pub mod prime_cache;
+
+use std::{
+    cmp::max,
+    collections::{HashMap, VecDeque},
+    ops::{Mul, Sub},
+};
 
 use integer_sqrt::IntegerSquareRoot;
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::euler_tools::{BoundedRefIterator, gcd};
+
+/// Bit-packed primality table that only stores odd numbers, so each byte covers 16 integers.
+/// A set bit marks an odd composite (or 1), which lets the table start zeroed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OddPrimeTable {
+    composite_bits: Vec<u64>,
+    limit: usize,
+}
+
+impl OddPrimeTable {
+    const WORD_BITS: usize = u64::BITS as usize;
+
+    /// Creates a table for every number below the limit, with every odd number above 1 marked
+    /// as prime
+    fn new(limit: usize) -> Self {
+        let mut composite_bits = vec![0; Self::word_count(limit)];
+        // 1 isn't prime
+        composite_bits[0] = 1;
+        OddPrimeTable {
+            composite_bits,
+            limit,
+        }
+    }
 
-use crate::euler_tools::BoundedRefIterator;
+    fn word_count(limit: usize) -> usize {
+        (limit / 2).div_ceil(Self::WORD_BITS).max(1)
+    }
+
+    fn is_prime(&self, n: usize) -> bool {
+        debug_assert!(n < self.limit);
+        if n.is_multiple_of(2) {
+            return n == 2;
+        }
+        let index = n / 2;
+        self.composite_bits[index / Self::WORD_BITS] & (1 << (index % Self::WORD_BITS)) == 0
+    }
+
+    /// Marks every odd multiple of the odd prime p in [p^2, bound) as composite
+    fn sieve_multiples_below(&mut self, p: usize, bound: usize) {
+        // consecutive odd multiples are 2p apart, which is p apart in the odd-only indices
+        for index in ((p * p / 2)..bound.min(self.limit) / 2).step_by(p) {
+            self.composite_bits[index / Self::WORD_BITS] |= 1 << (index % Self::WORD_BITS);
+        }
+    }
+
+    /// Marks the odd multiples of the odd base primes within a run of words, starting at
+    /// first_word. Each call only touches its own words, so runs can be sieved independently.
+    fn sieve_words(
+        words: &mut [u64],
+        first_word: usize,
+        index_limit: usize,
+        base_primes: &[usize],
+    ) {
+        let start_index = first_word * Self::WORD_BITS;
+        let end_index = index_limit.min((first_word + words.len()) * Self::WORD_BITS);
+        for p in base_primes {
+            let first_multiple = p * p / 2;
+            if first_multiple >= end_index {
+                break;
+            }
+            let mut index = if first_multiple >= start_index {
+                first_multiple
+            } else {
+                first_multiple + (start_index - first_multiple).div_ceil(*p) * p
+            };
+            while index < end_index {
+                words[(index - start_index) / Self::WORD_BITS] |= 1 << (index % Self::WORD_BITS);
+                index += p;
+            }
+        }
+    }
+
+    /// Iterates through the odd primes in [start, end) in increasing order
+    fn odd_primes_between(&self, start: usize, end: usize) -> impl Iterator<Item = usize> + '_ {
+        let index_limit = end.min(self.limit) / 2;
+        let start_index = start / 2;
+        let end_word = index_limit.div_ceil(Self::WORD_BITS);
+        (start_index / Self::WORD_BITS..end_word).flat_map(move |word_index| {
+            let mut prime_bits = !self.composite_bits[word_index];
+            if word_index == start_index / Self::WORD_BITS {
+                // clear the bits for numbers below start
+                prime_bits &= !((1u64 << (start_index % Self::WORD_BITS)) - 1);
+            }
+            std::iter::from_fn(move || {
+                if prime_bits == 0 {
+                    return None;
+                }
+                let bit = prime_bits.trailing_zeros() as usize;
+                prime_bits &= prime_bits - 1;
+                Some(word_index * Self::WORD_BITS + bit)
+            })
+            .take_while(move |index| *index < index_limit)
+            .map(|index| 2 * index + 1)
+        })
+    }
+}
 
 #[derive(Debug)]
 pub struct Primes {
-    prime_table: Vec<bool>,
+    prime_table: OddPrimeTable,
     primes: Vec<u32>,
     limit: u32,
 }
@@ -24,36 +128,103 @@ impl Primes {
     }
 
     pub fn find_primes(limit: usize) -> Self {
-        let limit = max(limit, 1);
-        let sqrt_limit = integer_sqrt::IntegerSquareRoot::integer_sqrt(&limit);
+        Primes::find_primes_with_threads(limit, 1)
+    }
 
-        let mut prime_table = vec![true; limit];
-        prime_table[0] = false;
-        prime_table[1] = false;
+    /// Same as find_primes, but sieves and collects the primes on every available thread
+    pub fn find_primes_parallel(limit: usize) -> Self {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        Primes::find_primes_with_threads(limit, threads)
+    }
 
-        // sqrt x is much less than x/log x ~= number of primes under x,
-        // so we won't reserve extra capacity, but it's still better than nothing
-        let mut primes: Vec<u32> = Vec::with_capacity(sqrt_limit);
+    pub fn find_primes_with_threads(limit: usize, threads: usize) -> Self {
+        // number of words sieved at a time, so the working set stays in cache
+        const BLOCK_WORDS: usize = 1 << 12;
 
-        // If a number is not prime, it is guarunteed to have a divisor <= its square root
-        for n in 2..=sqrt_limit {
-            if !prime_table[n] {
-                continue;
-            }
+        let limit = max(limit, 1);
+        let threads = max(threads, 1);
+        let sqrt_limit = integer_sqrt::IntegerSquareRoot::integer_sqrt(&limit);
 
-            primes.push(n as u32);
+        let mut prime_table = OddPrimeTable::new(limit);
 
-            for n_multiple in (2 * n..limit).step_by(n) {
-                prime_table[n_multiple] = false;
+        // If a number is not prime, it is guarunteed to have a divisor <= its square root.
+        // Sieving up to sqrt_limit first gives us the primes needed for the rest of the table.
+        let mut base_primes = Vec::new();
+        for n in (3..=sqrt_limit).step_by(2) {
+            if prime_table.is_prime(n) {
+                base_primes.push(n);
+                prime_table.sieve_multiples_below(n, sqrt_limit + 1);
             }
         }
 
-        let basic_start = sqrt_limit + 1;
-        let next_odd_start = basic_start | 1;
-        for i in (next_odd_start..limit).step_by(2) {
-            if prime_table[i] {
-                primes.push(i as u32);
+        let index_limit = limit / 2;
+        let chunk_words = prime_table.composite_bits.len().div_ceil(threads);
+        let base_primes = &base_primes;
+        let sieve_chunk = move |chunk_index: usize, chunk: &mut [u64]| {
+            let first_word = chunk_index * chunk_words;
+            for (block_index, block) in chunk.chunks_mut(BLOCK_WORDS).enumerate() {
+                OddPrimeTable::sieve_words(
+                    block,
+                    first_word + block_index * BLOCK_WORDS,
+                    index_limit,
+                    base_primes,
+                );
             }
+        };
+        if threads == 1 {
+            // no point paying for a thread when there's only one chunk
+            sieve_chunk(0, &mut prime_table.composite_bits);
+        } else {
+            std::thread::scope(|scope| {
+                for (chunk_index, chunk) in prime_table
+                    .composite_bits
+                    .chunks_mut(chunk_words)
+                    .enumerate()
+                {
+                    scope.spawn(move || sieve_chunk(chunk_index, chunk));
+                }
+            });
+        }
+
+        Primes::from_table(prime_table, threads)
+    }
+
+    /// Collects the primes out of a fully sieved table, splitting the work between threads
+    fn from_table(prime_table: OddPrimeTable, threads: usize) -> Self {
+        let limit = prime_table.limit;
+
+        // sqrt x is much less than x/log x ~= number of primes under x,
+        // so we won't reserve extra capacity, but it's still better than nothing
+        let mut primes: Vec<u32> = Vec::with_capacity(limit.integer_sqrt());
+        if limit > 2 {
+            primes.push(2);
+        }
+        let chunk_words = prime_table.composite_bits.len().div_ceil(max(threads, 1));
+        let chunk_numbers = chunk_words * OddPrimeTable::WORD_BITS * 2;
+        let prime_table_ref = &prime_table;
+        let collect_chunk = move |chunk_index: usize| -> Vec<u32> {
+            let start = max(chunk_index * chunk_numbers, 3);
+            prime_table_ref
+                .odd_primes_between(start, (chunk_index + 1) * chunk_numbers)
+                .map(|p| p as u32)
+                .collect()
+        };
+        let chunk_count = limit.div_ceil(chunk_numbers);
+        let prime_chunks: Vec<Vec<u32>> = if chunk_count <= 1 {
+            (0..chunk_count).map(collect_chunk).collect()
+        } else {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..chunk_count)
+                    .map(|chunk_index| scope.spawn(move || collect_chunk(chunk_index)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("prime collection thread panicked"))
+                    .collect()
+            })
+        };
+        for prime_chunk in prime_chunks {
+            primes.extend(prime_chunk);
         }
 
         Primes {
@@ -65,7 +236,7 @@ impl Primes {
 
     pub fn is_prime_basic(&self, n: u32) -> bool {
         if n < self.limit {
-            self.prime_table[n as usize]
+            self.prime_table.is_prime(n as usize)
         } else {
             panic!("Tried to find a prime beyond what has been computed")
         }
@@ -73,7 +244,7 @@ impl Primes {
 
     pub fn is_prime(&self, n: u32) -> bool {
         if n < self.limit {
-            return self.prime_table[n as usize];
+            return self.prime_table.is_prime(n as usize);
         } else if n < self.limit * self.limit {
             for prime in self.prime_iterator() {
                 if n % prime == 0 {
@@ -94,6 +265,36 @@ impl Primes {
         self.primes.iter()
     }
 
+    /// All of the computed primes in the range, as a slice
+    pub fn primes_in_range(&self, range: std::ops::Range<u32>) -> &[u32] {
+        let start = self.primes.partition_point(|p| *p < range.start);
+        let end = self.primes.partition_point(|p| *p < range.end);
+        &self.primes[start..end.max(start)]
+    }
+
+    /// Splits the primes in the range into one contiguous run per available thread, and runs
+    /// `process` on each run in parallel. The results are returned in the same order as the runs.
+    pub fn par_prime_iterator<T, F>(&self, range: std::ops::Range<u32>, process: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(std::slice::Iter<u32>) -> T + Sync,
+    {
+        let primes = self.primes_in_range(range);
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = max(primes.len().div_ceil(threads), 1);
+        let process = &process;
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = primes
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || process(chunk.iter())))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("prime iterator thread panicked"))
+                .collect()
+        })
+    }
+
     pub fn prime_factorize(&self, n: u64) -> Vec<u64> {
         if n < self.limit as u64 && self.is_prime(n as u32) || n == 0 || n == 1 {
             return vec![n];
@@ -232,51 +433,24 @@ impl Primes {
     }
 
     pub fn gcd(&self, a: u64, b: u64) -> u64 {
-        self.find_gcd_and_reduce(&mut a.to_owned(), &mut b.to_owned())
+        gcd::gcd(a, b)
     }
     pub fn gcd_signed(&self, a: i64, b: i64) -> u64 {
-        self.find_gcd_and_reduce_signed(&mut a.to_owned(), &mut b.to_owned())
+        gcd::gcd(a.unsigned_abs(), b.unsigned_abs())
     }
 
     pub fn find_gcd_and_reduce(&self, a: &mut u64, b: &mut u64) -> u64 {
-        if *a == 1 || *b == 1 {
-            return 1;
-        }
-        if *a == 0 || *a % *b == 0 {
-            let gcd = *b;
-            *a /= gcd;
-            *b = 1;
-            return gcd;
-        }
-        if *b == 0 || *b % *a == 0 {
-            let gcd = *a;
-            *b /= gcd;
-            *a = 1;
-            return gcd;
-        }
-
-        // TODO I should actually verify that this approach works better finding both factors
-        // and comparing the lists
-        let mut gcd = 1;
-        for a_factor in self.prime_factorize(*a) {
-            if *b % a_factor == 0 {
-                *b /= a_factor;
-                gcd *= a_factor;
-            }
-        }
-
-        *a /= gcd;
-
-        gcd
+        gcd::reduce_by_gcd(a, b)
     }
 
+    /// Reduces a/b so that the sign ends up on a
     pub fn find_gcd_and_reduce_signed(&self, a: &mut i64, b: &mut i64) -> u64 {
         let a_sign = a.signum() * b.signum();
 
         let mut unsigned_a = a.unsigned_abs();
         let mut unsigned_b = b.unsigned_abs();
 
-        let gcd = self.find_gcd_and_reduce(&mut unsigned_a, &mut unsigned_b);
+        let gcd = gcd::reduce_by_gcd(&mut unsigned_a, &mut unsigned_b);
 
         *a = a_sign * (unsigned_a as i64);
         *b = unsigned_b as i64;
@@ -285,6 +459,350 @@ impl Primes {
     }
 }
 
+/// Sums of some function over the primes, evaluated at every distinct value of floor(n / k).
+///
+/// This is the table built by the Lucy_Hedgehog algorithm. It takes roughly O(n^(3/4)) time and
+/// O(sqrt n) space, so it can handle bounds far beyond what `Primes::find_primes` can sieve.
+#[derive(Debug, Clone)]
+pub struct PrimeSumTable<T> {
+    n: u64,
+    sqrt_n: u64,
+    /// small_sums[v] holds the sum for v, for v <= sqrt(n)
+    small_sums: Vec<T>,
+    /// large_sums[k] holds the sum for n / k, for k <= sqrt(n)
+    large_sums: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T: Clone> PrimeSumTable<T> {
+    /// Builds the table from scratch.
+    ///
+    /// `initial_sum(v)` must give the sum over every integer in [2, v] as if they were all prime.
+    /// `remove_multiples(sum, p, sum_at_v_over_p, sum_at_p_minus_1)` is called once for every
+    /// prime p <= sqrt(v) and must remove the contribution of the numbers whose smallest prime
+    /// factor is p, i.e. p * j for the j counted by `sum_at_v_over_p - sum_at_p_minus_1`.
+    pub fn new<I, R>(n: u64, initial_sum: I, remove_multiples: R) -> Self
+    where
+        I: Fn(u64) -> T,
+        R: Fn(&mut T, u64, &T, &T),
+    {
+        let sqrt_n = n.integer_sqrt();
+
+        let mut small_sums: Vec<T> = (0..=sqrt_n).map(&initial_sum).collect();
+        // large_sums[0] is never read, but it keeps the indexing consistent with the definition
+        let mut large_sums: Vec<T> = (0..=sqrt_n)
+            .map(|k| initial_sum(n.checked_div(k).unwrap_or(n)))
+            .collect();
+
+        let primes = Primes::find_primes(sqrt_n as usize + 1);
+        for p in primes.prime_iterator() {
+            let p = *p as u64;
+            let p_squared = p * p;
+            if p_squared > n {
+                break;
+            }
+            let sum_below_p = small_sums[(p - 1) as usize].clone();
+
+            for k in 1..=sqrt_n {
+                let v = n / k;
+                if v < p_squared {
+                    break;
+                }
+                let sum_at_v_over_p = if k * p <= sqrt_n {
+                    large_sums[(k * p) as usize].clone()
+                } else {
+                    small_sums[(v / p) as usize].clone()
+                };
+                remove_multiples(
+                    &mut large_sums[k as usize],
+                    p,
+                    &sum_at_v_over_p,
+                    &sum_below_p,
+                );
+            }
+
+            for v in (p_squared..=sqrt_n).rev() {
+                let sum_at_v_over_p = small_sums[(v / p) as usize].clone();
+                remove_multiples(
+                    &mut small_sums[v as usize],
+                    p,
+                    &sum_at_v_over_p,
+                    &sum_below_p,
+                );
+            }
+        }
+
+        PrimeSumTable {
+            n,
+            sqrt_n,
+            small_sums,
+            large_sums,
+        }
+    }
+
+    /// Finds the sum over all primes <= v.
+    /// v must be of the form n / k, otherwise it wasn't computed.
+    pub fn get(&self, v: u64) -> &T {
+        if v <= self.sqrt_n {
+            &self.small_sums[v as usize]
+        } else {
+            let k = self.n / v;
+            assert_eq!(self.n / k, v, "{v} is not of the form {} / k", self.n);
+            &self.large_sums[k as usize]
+        }
+    }
+
+    /// The sum over all primes <= n
+    pub fn total(&self) -> &T {
+        self.get(self.n)
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Copy + Sub<Output = T> + Mul<Output = T>> PrimeSumTable<T> {
+    /// Sums a completely multiplicative function f over the primes.
+    /// `prefix_sum(v)` must give the sum of f(i) for 2 <= i <= v.
+    pub fn completely_multiplicative<F, P>(n: u64, f: F, prefix_sum: P) -> Self
+    where
+        F: Fn(u64) -> T,
+        P: Fn(u64) -> T,
+    {
+        PrimeSumTable::new(n, prefix_sum, |sum, p, sum_at_v_over_p, sum_below_p| {
+            *sum = *sum - f(p) * (*sum_at_v_over_p - *sum_below_p);
+        })
+    }
+}
+
+/// Counts the primes <= n
+#[allow(dead_code)]
+pub fn prime_pi(n: u64) -> u64 {
+    *PrimeSumTable::completely_multiplicative(n, |_| 1, |v| v.saturating_sub(1)).total()
+}
+
+/// Sums the primes <= n
+pub fn prime_sum(n: u64) -> u128 {
+    *PrimeSumTable::completely_multiplicative(
+        n,
+        |p| p as u128,
+        |v| {
+            let v = v as u128;
+            (v * (v + 1) / 2).saturating_sub(1)
+        },
+    )
+    .total()
+}
+
+/// Sums p^k for all primes p <= n. Panics if the sum of i^k for all i <= n doesn't fit in a u128.
+#[allow(dead_code)]
+pub fn prime_power_sum(n: u64, k: u32) -> u128 {
+    *PrimeSumTable::completely_multiplicative(
+        n,
+        |p| (p as u128).pow(k),
+        |v| {
+            if v < 2 {
+                return 0;
+            }
+            (power_sum(v, k) - 1u32)
+                .to_u128()
+                .expect("sum of powers fits in a u128")
+        },
+    )
+    .total()
+}
+
+/// Computes 1^k + 2^k + ... + v^k exactly, using the recurrence from Faulhaber's formula:
+/// (m+1) S_m(v) = (v+1)^(m+1) - 1 - sum_{j < m} (m+1 choose j) S_j(v)
+fn power_sum(v: u64, k: u32) -> BigUint {
+    if v == 0 {
+        return BigUint::zero();
+    }
+
+    let mut power_sums: Vec<BigUint> = Vec::with_capacity(k as usize + 1);
+    let mut pascal_row = vec![BigUint::one()];
+    for m in 0..=k as usize {
+        let mut next_pascal_row = vec![BigUint::one(); m + 2];
+        for j in 1..=m {
+            next_pascal_row[j] = &pascal_row[j - 1] + &pascal_row[j];
+        }
+        pascal_row = next_pascal_row;
+
+        let mut power_sum = (BigUint::from(v) + 1u32).pow(m as u32 + 1) - 1u32;
+        for (binomial, lower_power_sum) in pascal_row.iter().zip(power_sums.iter()) {
+            power_sum -= binomial * lower_power_sum;
+        }
+        power_sums.push(power_sum / BigUint::from(m + 1));
+    }
+
+    power_sums.pop().expect("computed at least S_0")
+}
+
+/// Counts the primes <= n in each residue class modulo `modulus`.
+/// The count of primes congruent to a is at index a.
+#[allow(dead_code)]
+pub fn prime_pi_residues(n: u64, modulus: u64) -> Vec<u64> {
+    assert_ne!(modulus, 0, "modulus must be positive");
+    let table = PrimeSumTable::new(
+        n,
+        |v| {
+            let mut counts = vec![0; modulus as usize];
+            if v >= 2 {
+                for (residue, count) in counts.iter_mut().enumerate() {
+                    let residue = residue as u64;
+                    // number of i in [0, v] congruent to residue
+                    *count = if residue <= v {
+                        (v - residue) / modulus + 1
+                    } else {
+                        0
+                    };
+                }
+                // 0 and 1 aren't prime
+                counts[0] -= 1;
+                counts[(1 % modulus) as usize] -= 1;
+            }
+            counts
+        },
+        |counts: &mut Vec<u64>, p, counts_at_v_over_p, counts_below_p| {
+            for (residue, (above, below)) in counts_at_v_over_p
+                .iter()
+                .zip(counts_below_p.iter())
+                .enumerate()
+            {
+                counts[(residue as u64 * p % modulus) as usize] -= above - below;
+            }
+        },
+    );
+    table.total().clone()
+}
+
+/// Counts the primes p <= n where p ≡ a (mod modulus)
+#[allow(dead_code)]
+pub fn prime_pi_congruent(n: u64, a: u64, modulus: u64) -> u64 {
+    prime_pi_residues(n, modulus)[(a % modulus) as usize]
+}
+
+/// Computes Euler's totient for every number below the limit
+#[allow(dead_code)]
+pub fn phi_sieve(limit: usize) -> Vec<u64> {
+    let mut phi: Vec<u64> = (0..limit as u64).collect();
+    for n in 2..limit {
+        // phi[n] is only untouched if no smaller prime divides n
+        if phi[n] != n as u64 {
+            continue;
+        }
+        for multiple in (n..limit).step_by(n) {
+            phi[multiple] -= phi[multiple] / n as u64;
+        }
+    }
+    phi
+}
+
+/// Computes the Möbius function for every number below the limit. mu(0) is defined as 0.
+#[allow(dead_code)]
+pub fn mobius_sieve(limit: usize) -> Vec<i8> {
+    let mut mobius = vec![1i8; limit];
+    if limit > 0 {
+        mobius[0] = 0;
+    }
+    let mut is_composite = vec![false; limit];
+    for n in 2..limit {
+        if is_composite[n] {
+            continue;
+        }
+        for multiple in (n..limit).step_by(n) {
+            if multiple != n {
+                is_composite[multiple] = true;
+            }
+            mobius[multiple] = -mobius[multiple];
+        }
+        for square_multiple in (n.saturating_mul(n)..limit).step_by(n * n) {
+            mobius[square_multiple] = 0;
+        }
+    }
+    mobius
+}
+
+/// Memoized summatory function F of some arithmetic function f, using the identity
+/// sum_{d=1}^{v} F(v / d) = G(v)
+/// for a known G. The sum is split into blocks where v / d is constant (the Dirichlet hyperbola
+/// method), so each value takes O(sqrt v) work, and values up to about n^(2/3) come from a sieve.
+struct SummatoryFunction {
+    small_sums: Vec<i128>,
+    large_sums: HashMap<u64, i128>,
+    identity: fn(u64) -> i128,
+}
+
+impl SummatoryFunction {
+    fn new(n: u64, identity: fn(u64) -> i128, sieve: fn(usize) -> Vec<i128>) -> Self {
+        let sieve_limit = ((n as f64).powf(2.0 / 3.0) as usize)
+            .max(2)
+            .min(n as usize + 1);
+        let mut small_sums = sieve(sieve_limit);
+        for i in 1..small_sums.len() {
+            small_sums[i] += small_sums[i - 1];
+        }
+        SummatoryFunction {
+            small_sums,
+            large_sums: HashMap::new(),
+            identity,
+        }
+    }
+
+    fn get(&mut self, v: u64) -> i128 {
+        if let Some(sum) = self.small_sums.get(v as usize) {
+            return *sum;
+        }
+        if let Some(sum) = self.large_sums.get(&v) {
+            return *sum;
+        }
+
+        let mut sum = (self.identity)(v);
+        let mut d = 2;
+        while d <= v {
+            let quotient = v / d;
+            let last_d = v / quotient;
+            sum -= (last_d - d + 1) as i128 * self.get(quotient);
+            d = last_d + 1;
+        }
+
+        self.large_sums.insert(v, sum);
+        sum
+    }
+}
+
+/// Sums Euler's totient for every number from 1 to n in roughly O(n^(2/3)) time.
+/// This is one more than the number of reduced fractions with denominators <= n in (0, 1).
+#[allow(dead_code)]
+pub fn totient_sum(n: u64) -> u128 {
+    SummatoryFunction::new(
+        n,
+        |v| v as i128 * (v as i128 + 1) / 2,
+        |limit| {
+            phi_sieve(limit)
+                .into_iter()
+                .map(|phi| phi as i128)
+                .collect()
+        },
+    )
+    .get(n) as u128
+}
+
+/// Computes the Mertens function, the sum of the Möbius function from 1 to n, in roughly
+/// O(n^(2/3)) time.
+#[allow(dead_code)]
+pub fn mertens(n: u64) -> i64 {
+    SummatoryFunction::new(
+        n,
+        |v| if v >= 1 { 1 } else { 0 },
+        |limit| {
+            mobius_sieve(limit)
+                .into_iter()
+                .map(|mu| mu as i128)
+                .collect()
+        },
+    )
+    .get(n) as i64
+}
+
 #[derive(Debug)]
 pub struct CoprimePairsIterator {
     unreviewed_pairs: VecDeque<(u64, u64)>,
@@ -330,7 +848,10 @@ impl Iterator for CoprimePairsIterator {
 
 #[cfg(test)]
 mod tests {
-    use super::{CoprimePairsIterator, Primes};
+    use super::{
+        CoprimePairsIterator, Primes, mertens, mobius_sieve, phi_sieve, prime_pi,
+        prime_pi_congruent, prime_pi_residues, prime_power_sum, prime_sum, totient_sum,
+    };
 
     #[test]
     fn primes_generated() {
@@ -364,6 +885,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn small_limits() {
+        for limit in 0..4 {
+            let primes = Primes::find_primes(limit);
+            assert_eq!(
+                primes.primes,
+                [2].into_iter()
+                    .filter(|p| *p < limit as u32)
+                    .collect::<Vec<_>>(),
+                "primes below {limit}"
+            );
+        }
+    }
+
+    #[test]
+    fn bit_table_matches_trial_division() {
+        // large enough to span several words in the table, and not a multiple of the word size
+        let primes = Primes::find_primes(10_007);
+        for n in 0..10_007u32 {
+            let trial_division = n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0);
+            assert_eq!(primes.is_prime_basic(n), trial_division, "primality of {n}");
+        }
+        assert_eq!(primes.total_primes(), 1229);
+    }
+
+    #[test]
+    fn parallel_sieve_identical() {
+        for limit in [0, 1, 2, 3, 100, 129, 10_007, 1_000_000] {
+            let primes = Primes::find_primes(limit);
+            for threads in [2, 3, 8] {
+                let parallel_primes = Primes::find_primes_with_threads(limit, threads);
+                assert_eq!(parallel_primes.limit, primes.limit);
+                assert_eq!(
+                    parallel_primes.primes, primes.primes,
+                    "primes below {limit}"
+                );
+                assert_eq!(parallel_primes.prime_table, primes.prime_table);
+            }
+        }
+        assert_eq!(
+            Primes::find_primes_parallel(10_007).primes,
+            Primes::find_primes(10_007).primes
+        );
+    }
+
+    #[test]
+    fn parallel_prime_iterator() {
+        let primes = Primes::find_primes(100_000);
+        assert_eq!(primes.primes_in_range(10..30), &[11, 13, 17, 19, 23, 29]);
+        let (start, end) = (30, 10);
+        assert_eq!(primes.primes_in_range(start..end), &[] as &[u32]);
+
+        let sums = primes.par_prime_iterator(1_000..100_000, |chunk| {
+            chunk.map(|p| *p as u64).sum::<u64>()
+        });
+        assert_eq!(
+            sums.iter().sum::<u64>(),
+            primes
+                .bounded_prime_iterator(100_000)
+                .map(|p| *p as u64)
+                .sum::<u64>()
+                - primes
+                    .bounded_prime_iterator(1_000)
+                    .map(|p| *p as u64)
+                    .sum::<u64>()
+        );
+    }
+
     #[test]
     fn primes_factorized() {
         let primes = Primes::find_primes(100);
@@ -544,6 +1133,117 @@ mod tests {
         assert_eq!(primes.find_gcd_and_reduce_signed(&mut a, &mut b), 10);
         assert_eq!(a, -1);
         assert_eq!(b, 2);
+
+        // these used to need a sieve that could factor a
+        assert_eq!(primes.gcd(1_000_003 * 6, 1_000_003 * 10), 2_000_006);
+        let mut a = 12;
+        let mut b = 0;
+        assert_eq!(primes.find_gcd_and_reduce(&mut a, &mut b), 12);
+        assert_eq!((a, b), (1, 0));
+    }
+
+    #[test]
+    fn prime_pi_matches_sieve() {
+        let primes = Primes::find_primes(1000);
+        for n in 0..1000 {
+            assert_eq!(
+                prime_pi(n),
+                primes.bounded_prime_iterator(n as u32 + 1).count() as u64,
+                "pi({n})"
+            );
+        }
+    }
+
+    #[test]
+    fn prime_pi_large() {
+        assert_eq!(prime_pi(1_000_000), 78_498);
+        assert_eq!(prime_pi(1_000_000_000), 50_847_534);
+    }
+
+    #[test]
+    fn prime_sums_match_sieve() {
+        let primes = Primes::find_primes(500);
+        for n in 0..500 {
+            let bounded_primes = || primes.bounded_prime_iterator(n as u32 + 1);
+            assert_eq!(
+                prime_sum(n),
+                bounded_primes().map(|p| *p as u128).sum(),
+                "sum to {n}"
+            );
+            assert_eq!(
+                prime_power_sum(n, 0),
+                bounded_primes().count() as u128,
+                "count to {n}"
+            );
+            assert_eq!(
+                prime_power_sum(n, 2),
+                bounded_primes().map(|p| (*p as u128).pow(2)).sum(),
+                "sum of squares to {n}"
+            );
+        }
+    }
+
+    #[test]
+    fn prime_pi_residues_match_sieve() {
+        let primes = Primes::find_primes(500);
+        for modulus in 1..=12 {
+            let mut expected = vec![0; modulus];
+            for p in primes.prime_iterator() {
+                expected[*p as usize % modulus] += 1;
+            }
+            assert_eq!(prime_pi_residues(499, modulus as u64), expected);
+        }
+        assert_eq!(prime_pi_congruent(1_000_000, 1, 4), 39_175);
+        assert_eq!(prime_pi_congruent(1_000_000, 3, 4), 39_322);
+    }
+
+    #[test]
+    fn phi_sieved() {
+        assert_eq!(phi_sieve(13), vec![0, 1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+    }
+
+    #[test]
+    fn mobius_sieved() {
+        assert_eq!(
+            mobius_sieve(13),
+            vec![0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]
+        );
+    }
+
+    #[test]
+    fn totient_sum_matches_sieve() {
+        let phi = phi_sieve(1000);
+        let mut sum = 0;
+        for (n, phi_n) in phi.iter().enumerate().skip(1) {
+            sum += *phi_n as u128;
+            assert_eq!(totient_sum(n as u64), sum, "totient sum to {n}");
+        }
+        assert_eq!(totient_sum(0), 0);
+    }
+
+    #[test]
+    fn totient_sum_large() {
+        // there are 21 reduced proper fractions with denominators <= 8
+        assert_eq!(totient_sum(8) - 1, 21);
+        assert_eq!(totient_sum(1_000_000) - 1, 303_963_552_391);
+        assert_eq!(totient_sum(1_000_000_000), 303_963_551_173_008_414);
+    }
+
+    #[test]
+    fn mertens_matches_sieve() {
+        let mobius = mobius_sieve(1000);
+        let mut sum = 0;
+        for (n, mobius_n) in mobius.iter().enumerate().skip(1) {
+            sum += *mobius_n as i64;
+            assert_eq!(mertens(n as u64), sum, "mertens of {n}");
+        }
+        assert_eq!(mertens(0), 0);
+    }
+
+    #[test]
+    fn mertens_large() {
+        assert_eq!(mertens(1_000_000), 212);
+        assert_eq!(mertens(1_000_000_000), -222);
     }
 
     #[test]
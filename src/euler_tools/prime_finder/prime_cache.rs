@@ -0,0 +1,200 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use super::{OddPrimeTable, Primes};
+
+/// Identifies a prime cache file
+const MAGIC: &[u8; 8] = b"PEPRIMES";
+/// Bumped whenever the layout of the table or the file changes
+const VERSION: u32 = 1;
+/// magic, version, limit, and word count
+const HEADER_LEN: usize = 8 + 4 + 8 + 8;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PrimeCacheError {
+    Io(io::ErrorKind),
+    NotAPrimeCache,
+    UnsupportedVersion(u32),
+    Truncated,
+    ChecksumMismatch,
+}
+
+impl From<io::Error> for PrimeCacheError {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::UnexpectedEof => PrimeCacheError::Truncated,
+            kind => PrimeCacheError::Io(kind),
+        }
+    }
+}
+
+/// 64-bit FNV-1a. It's not cryptographic, but it catches truncated or corrupted files.
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[allow(dead_code)]
+impl Primes {
+    /// Writes the sieved table to a file. Only the bit table is stored, the list of primes is
+    /// rebuilt from it when loading.
+    ///
+    /// Layout (all little endian): "PEPRIMES", version: u32, limit: u64, word count: u64,
+    /// the table's words: u64..., then an FNV-1a checksum: u64 of everything before it.
+    pub fn write_cache(&self, path: &Path) -> io::Result<()> {
+        let words = &self.prime_table.composite_bits;
+        let mut bytes = Vec::with_capacity(HEADER_LEN + 8 * words.len() + 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.prime_table.limit as u64).to_le_bytes());
+        bytes.extend_from_slice(&(words.len() as u64).to_le_bytes());
+        for word in words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes.extend_from_slice(&checksum(&bytes).to_le_bytes());
+
+        // write to a temporary file first so a crash never leaves a half-written cache behind
+        let temp_path = path.with_extension("tmp");
+        fs::File::create(&temp_path)?.write_all(&bytes)?;
+        fs::rename(temp_path, path)
+    }
+
+    pub fn read_cache(path: &Path) -> Result<Primes, PrimeCacheError> {
+        let mut bytes = Vec::new();
+        fs::File::open(path)?.read_to_end(&mut bytes)?;
+
+        if !bytes.starts_with(MAGIC) {
+            return Err(PrimeCacheError::NotAPrimeCache);
+        }
+        if bytes.len() < HEADER_LEN + 8 {
+            return Err(PrimeCacheError::Truncated);
+        }
+        let read_u64 = |offset: usize| {
+            u64::from_le_bytes(
+                bytes[offset..offset + 8]
+                    .try_into()
+                    .expect("slice is 8 bytes"),
+            )
+        };
+
+        let version = u32::from_le_bytes(bytes[8..12].try_into().expect("slice is 4 bytes"));
+        if version != VERSION {
+            return Err(PrimeCacheError::UnsupportedVersion(version));
+        }
+        let limit = read_u64(12) as usize;
+        let word_count = read_u64(20) as usize;
+        if word_count != OddPrimeTable::word_count(limit) {
+            return Err(PrimeCacheError::NotAPrimeCache);
+        }
+
+        let checksum_offset = HEADER_LEN + 8 * word_count;
+        if bytes.len() != checksum_offset + 8 {
+            return Err(PrimeCacheError::Truncated);
+        }
+        if checksum(&bytes[..checksum_offset]) != read_u64(checksum_offset) {
+            return Err(PrimeCacheError::ChecksumMismatch);
+        }
+
+        let prime_table = OddPrimeTable {
+            composite_bits: (0..word_count)
+                .map(|word_index| read_u64(HEADER_LEN + 8 * word_index))
+                .collect(),
+            limit,
+        };
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        Ok(Primes::from_table(prime_table, threads))
+    }
+
+    /// Loads the primes below the limit from cache_dir, or sieves them and saves them there for
+    /// next time. Any problem reading or writing the cache just falls back to sieving.
+    ///
+    /// This is meant for development loops and tools. Problems should keep calling find_primes,
+    /// so that timing runs measure the real computation.
+    pub fn load_or_compute(limit: usize, cache_dir: &Path) -> Primes {
+        let path = cache_path(limit, cache_dir);
+        if let Ok(primes) = Primes::read_cache(&path)
+            && primes.limit as usize == limit.max(1)
+        {
+            return primes;
+        }
+
+        let primes = Primes::find_primes_parallel(limit);
+        // a failed write only means we'll sieve again next time
+        let _ = fs::create_dir_all(cache_dir).and_then(|_| primes.write_cache(&path));
+        primes
+    }
+}
+
+fn cache_path(limit: usize, cache_dir: &Path) -> PathBuf {
+    cache_dir.join(format!("primes_below_{limit}.bin"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{PrimeCacheError, cache_path};
+    use crate::euler_tools::prime_finder::Primes;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("project-euler-rust-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = test_dir("round-trip");
+        let computed = Primes::load_or_compute(10_007, &dir);
+        assert!(cache_path(10_007, &dir).exists());
+
+        let loaded = Primes::load_or_compute(10_007, &dir);
+        assert_eq!(loaded.limit, computed.limit);
+        assert_eq!(loaded.primes, computed.primes);
+        assert_eq!(loaded.prime_table, computed.prime_table);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn corrupted_cache_rejected() {
+        let dir = test_dir("corrupted");
+        Primes::load_or_compute(1000, &dir);
+        let path = cache_path(1000, &dir);
+
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[40] ^= 1;
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(
+            Primes::read_cache(&path).err(),
+            Some(PrimeCacheError::ChecksumMismatch)
+        );
+        // falls back to sieving, and repairs the cache
+        assert_eq!(
+            Primes::load_or_compute(1000, &dir).primes,
+            Primes::find_primes(1000).primes
+        );
+        assert!(Primes::read_cache(&path).is_ok());
+
+        fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+        assert_eq!(
+            Primes::read_cache(&path).err(),
+            Some(PrimeCacheError::Truncated)
+        );
+
+        fs::write(&path, b"definitely not a prime table at all").unwrap();
+        assert_eq!(
+            Primes::read_cache(&path).err(),
+            Some(PrimeCacheError::NotAPrimeCache)
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,22 +1,39 @@
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
 
-use num_traits::{Inv, One, PrimInt, Signed, Unsigned};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Inv, Num, One, PrimInt, Signed, ToPrimitive,
+    Unsigned, Zero,
+};
 
-use crate::euler_tools::prime_finder::Primes;
+use crate::euler_tools::{gcd::num_gcd, prime_finder::Primes};
 
-#[derive(Debug, Clone, Copy)]
-pub struct Ratio<N: PrimInt> {
-    pub numerator: N,
-    pub denominator: N,
+/// The integers a Ratio can be built from, which covers the primitive integers, BigInt and
+/// BigUint
+pub trait RatioInteger: Clone + Ord + Num + CheckedAdd + CheckedSub + CheckedMul {}
+impl<N: Clone + Ord + Num + CheckedAdd + CheckedSub + CheckedMul> RatioInteger for N {}
+
+/// A fraction that's always kept in lowest terms, with a positive denominator. That makes the
+/// derived equality and hashing agree with equality of values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ratio<N> {
+    numerator: N,
+    denominator: N,
 }
 
-impl<N: PrimInt> Ratio<N> {
-    #[allow(dead_code)]
+impl<N: RatioInteger> Ratio<N> {
     pub fn new(numerator: N, denominator: N) -> Ratio<N> {
+        assert!(!denominator.is_zero(), "denominator must be nonzero");
         Ratio {
             numerator,
             denominator,
         }
+        .reduced()
     }
     #[allow(dead_code)]
     pub fn new_int(numerator: N) -> Ratio<N> {
@@ -25,191 +42,598 @@ impl<N: PrimInt> Ratio<N> {
             denominator: N::one(),
         }
     }
+
+    #[allow(dead_code)]
+    pub fn numerator(&self) -> &N {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &N {
+        &self.denominator
+    }
+
+    /// Splits the ratio into its numerator and denominator
+    #[allow(dead_code)]
+    pub fn into_parts(self) -> (N, N) {
+        (self.numerator, self.denominator)
+    }
+
+    /// Divides out the gcd, and moves any sign onto the numerator
+    fn reduced(self) -> Ratio<N> {
+        let gcd = num_gcd(self.numerator.clone(), self.denominator.clone());
+        let (mut numerator, mut denominator) =
+            (self.numerator / gcd.clone(), self.denominator / gcd);
+        if denominator < N::zero() {
+            numerator = N::zero() - numerator;
+            denominator = N::zero() - denominator;
+        }
+        Ratio {
+            numerator,
+            denominator,
+        }
+    }
 }
 
-impl<N: PrimInt> One for Ratio<N> {
+impl<N: RatioInteger> Zero for Ratio<N> {
+    fn zero() -> Self {
+        Ratio::new_int(N::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+}
+impl<N: RatioInteger> One for Ratio<N> {
     fn one() -> Self {
         Ratio::new_int(N::one())
     }
 }
 
-// TODO I think I can clean up this implementation using macros, but I need to figure out
-// how they work for trait implementations first
+// Each operation cancels what it can before multiplying, so that the checked versions only fail
+// when the reduced result doesn't fit in N
 
-impl<O: PrimInt, N: PrimInt + Neg<Output = O> + Into<O>> Neg for Ratio<N> {
-    type Output = Ratio<O>;
+impl<N: RatioInteger> CheckedAdd for Ratio<N> {
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        // a/b + c/d = (a (d/g) + c (b/g)) / ((b/g) d) with g = gcd(b, d), and anything left to
+        // cancel divides g
+        let gcd = num_gcd(self.denominator.clone(), rhs.denominator.clone());
+        let left = self.denominator.clone() / gcd.clone();
+        let right = rhs.denominator.clone() / gcd.clone();
+        let numerator = self
+            .numerator
+            .checked_mul(&right)?
+            .checked_add(&rhs.numerator.checked_mul(&left)?)?;
+        let shared = num_gcd(numerator.clone(), gcd);
+        Some(Ratio {
+            numerator: numerator / shared.clone(),
+            denominator: left.checked_mul(&(rhs.denominator.clone() / shared))?,
+        })
+    }
+}
+impl<N: RatioInteger> CheckedSub for Ratio<N> {
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        // the same as addition, but unsigned types can't go below 0
+        let gcd = num_gcd(self.denominator.clone(), rhs.denominator.clone());
+        let left = self.denominator.clone() / gcd.clone();
+        let right = rhs.denominator.clone() / gcd.clone();
+        let numerator = self
+            .numerator
+            .checked_mul(&right)?
+            .checked_sub(&rhs.numerator.checked_mul(&left)?)?;
+        let shared = num_gcd(numerator.clone(), gcd);
+        Some(Ratio {
+            numerator: numerator / shared.clone(),
+            denominator: left.checked_mul(&(rhs.denominator.clone() / shared))?,
+        })
+    }
+}
+impl<N: RatioInteger> CheckedMul for Ratio<N> {
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        // cancel across the two fractions, since each one is already reduced
+        let first = num_gcd(self.numerator.clone(), rhs.denominator.clone());
+        let second = num_gcd(rhs.numerator.clone(), self.denominator.clone());
+        let numerator = (self.numerator.clone() / first.clone())
+            .checked_mul(&(rhs.numerator.clone() / second.clone()))?;
+        let denominator =
+            (self.denominator.clone() / second).checked_mul(&(rhs.denominator.clone() / first))?;
+        // 0 has the denominator 1
+        if numerator.is_zero() {
+            return Some(Ratio::zero());
+        }
+        Some(Ratio {
+            numerator,
+            denominator,
+        })
+    }
+}
+impl<N: RatioInteger> CheckedDiv for Ratio<N> {
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        self.checked_mul(&rhs.clone().inv())
+    }
+}
+
+impl<N: RatioInteger + Neg<Output = N>> Neg for Ratio<N> {
+    type Output = Ratio<N>;
 
     fn neg(self) -> Self::Output {
         Ratio {
             numerator: -self.numerator,
-            denominator: self.denominator.into(),
+            denominator: self.denominator,
         }
     }
 }
-impl<N: PrimInt> Inv for Ratio<N> {
+impl<N: RatioInteger> Inv for Ratio<N> {
     type Output = Ratio<N>;
 
+    /// Panics if the ratio is 0
     fn inv(self) -> Self::Output {
-        Ratio {
-            numerator: self.denominator,
-            denominator: self.numerator,
-        }
+        Ratio::new(self.denominator, self.numerator)
     }
 }
 
-impl<M: PrimInt, O: PrimInt, N: PrimInt + Mul<M, Output = O>> Mul<Ratio<M>> for Ratio<N> {
-    type Output = Ratio<O>;
+/// Implements an operator and its assigning version for ratios and for integers on the right, by
+/// way of the checked operation, panicking with the given message when that fails
+macro_rules! impl_ratio_operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $checked:ident, $message:literal) => {
+        impl<N: RatioInteger> $trait for Ratio<N> {
+            type Output = Ratio<N>;
 
-    fn mul(self, rhs: Ratio<M>) -> Self::Output {
-        Ratio {
-            numerator: self.numerator * rhs.numerator,
-            denominator: self.denominator * rhs.denominator,
+            fn $method(self, rhs: Ratio<N>) -> Self::Output {
+                self.$checked(&rhs).expect($message)
+            }
+        }
+        impl<N: RatioInteger> $trait<N> for Ratio<N> {
+            type Output = Ratio<N>;
+
+            fn $method(self, rhs: N) -> Self::Output {
+                self.$method(Ratio::new_int(rhs))
+            }
         }
+        impl<N: RatioInteger> $assign_trait for Ratio<N> {
+            fn $assign_method(&mut self, rhs: Ratio<N>) {
+                *self = self.clone().$method(rhs);
+            }
+        }
+        impl<N: RatioInteger> $assign_trait<N> for Ratio<N> {
+            fn $assign_method(&mut self, rhs: N) {
+                *self = self.clone().$method(rhs);
+            }
+        }
+    };
+}
+
+impl_ratio_operator!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    checked_add,
+    "Ratio addition overflowed"
+);
+impl_ratio_operator!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    checked_sub,
+    "Ratio subtraction overflowed"
+);
+impl_ratio_operator!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    checked_mul,
+    "Ratio multiplication overflowed"
+);
+impl_ratio_operator!(
+    Div,
+    div,
+    DivAssign,
+    div_assign,
+    checked_div,
+    "Ratio division overflowed or divided by 0"
+);
+
+/// Floored division for a positive divisor, so the remainder is never negative
+pub(crate) fn floor_div_rem<N: RatioInteger>(a: N, b: N) -> (N, N) {
+    let (quotient, remainder) = (a.clone() / b.clone(), a % b.clone());
+    if remainder < N::zero() {
+        (quotient - N::one(), remainder + b)
+    } else {
+        (quotient, remainder)
     }
 }
-impl<M: PrimInt, O: PrimInt, N: PrimInt + Mul<M, Output = O> + Into<O>> Mul<M> for Ratio<N> {
-    type Output = Ratio<O>;
 
-    fn mul(self, rhs: M) -> Self::Output {
-        Ratio {
-            numerator: self.numerator * rhs,
-            denominator: self.denominator.into(),
+#[allow(dead_code)]
+impl<N: RatioInteger> Ratio<N> {
+    /// The largest integer that's at most the ratio
+    pub fn floor(&self) -> N {
+        floor_div_rem(self.numerator.clone(), self.denominator.clone()).0
+    }
+
+    /// The smallest integer that's at least the ratio
+    pub fn ceil(&self) -> N {
+        let (quotient, remainder) = floor_div_rem(self.numerator.clone(), self.denominator.clone());
+        if remainder.is_zero() {
+            quotient
+        } else {
+            quotient + N::one()
+        }
+    }
+
+    /// The nearest integer, with halves rounded away from 0
+    pub fn round(&self) -> N {
+        let (quotient, remainder) = floor_div_rem(self.numerator.clone(), self.denominator.clone());
+        // compare remainder / denominator to 1/2 without doubling anything
+        let rest = self.denominator.clone() - remainder.clone();
+        match remainder.cmp(&rest) {
+            Ordering::Less => quotient,
+            Ordering::Equal if self.numerator < N::zero() => quotient,
+            _ => quotient + N::one(),
         }
     }
 }
-impl<M, N: PrimInt> MulAssign<M> for Ratio<N>
+
+#[allow(dead_code)]
+impl<N: RatioInteger> Ratio<N>
 where
-    Ratio<N>: Mul<M, Output = Ratio<N>>,
+    BigInt: From<N>,
 {
-    fn mul_assign(&mut self, rhs: M) {
-        let result = *self * rhs;
-        self.numerator = result.numerator;
-        self.denominator = result.denominator;
+    /// The nearest f64, rounding halfway cases to even. That includes subnormal results, which
+    /// have fewer bits to round to.
+    pub fn to_f64(&self) -> f64 {
+        let numerator = BigInt::from(self.numerator.clone());
+        if numerator.is_zero() {
+            return 0.0;
+        }
+        let denominator = BigInt::from(self.denominator.clone()).into_parts().1;
+        let (sign, numerator) = numerator.into_parts();
+        // numerator / denominator x 2^shift, as an exact fraction
+        let scaled = |shift: i64| {
+            if shift >= 0 {
+                (&numerator << shift as u64, denominator.clone())
+            } else {
+                (numerator.clone(), &denominator << (-shift) as u64)
+            }
+        };
+
+        // the ratio is in [2^exponent, 2^(exponent + 1))
+        let estimate = numerator.bits() as i64 - denominator.bits() as i64;
+        let (low, high) = scaled(-estimate);
+        let exponent = estimate - (low < high) as i64;
+        // scale so that the last bit the f64 keeps is the units digit. Below 2^-1022 that's
+        // always the 2^-1074 bit, so subnormals get rounded in the same step as everything else.
+        let shift = 52 - exponent.max(-1022);
+        let (numerator, denominator) = scaled(shift);
+        let quotient = &numerator / &denominator;
+        let remainder = numerator - &quotient * &denominator;
+        let mut quotient = quotient.to_u64().expect("the quotient has at most 53 bits");
+        match (remainder << 1u8).cmp(&denominator) {
+            Ordering::Greater => quotient += 1,
+            Ordering::Equal => quotient += quotient & 1,
+            Ordering::Less => {}
+        }
+
+        // rounding up can carry into 2^53, which is still exact
+        let magnitude = scale_by_power_of_two(quotient as f64, -shift);
+        if sign == Sign::Minus {
+            -magnitude
+        } else {
+            magnitude
+        }
     }
-}
 
-impl<D: PrimInt, O: PrimInt, N: PrimInt + Mul<D, Output = O>> Div<Ratio<D>> for Ratio<N> {
-    type Output = Ratio<O>;
+    /// Expands the ratio in the given radix, which can be from 2 to 36. Long division runs
+    /// through the remainders, and Brent's cycle detection finds where they start repeating in
+    /// O(prefix + period) steps without storing them.
+    pub fn to_decimal_expansion(&self, radix: u32) -> DecimalExpansion {
+        assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+        let (sign, numerator) = BigInt::from(self.numerator.clone()).into_parts();
+        let denominator = BigInt::from(self.denominator.clone()).into_parts().1;
+
+        // each remainder r gives the digit r radix / denominator and the next remainder
+        let step = |remainder: &BigUint| (remainder * radix) % &denominator;
+        let digit = |remainder: &BigUint| {
+            ((remainder * radix) / &denominator)
+                .to_u32()
+                .expect("digits are less than the radix")
+        };
+        let start = &numerator % &denominator;
+
+        // Brent's algorithm: find the period first, then the length of the prefix
+        let (mut power, mut period) = (1, 1);
+        let mut tortoise = start.clone();
+        let mut hare = step(&start);
+        while tortoise != hare {
+            if power == period {
+                tortoise = hare.clone();
+                power *= 2;
+                period = 0;
+            }
+            hare = step(&hare);
+            period += 1;
+        }
+        let mut tortoise = start.clone();
+        let mut hare = (0..period).fold(start, |remainder, _| step(&remainder));
+        let mut prefix = Vec::new();
+        while tortoise != hare {
+            prefix.push(digit(&tortoise));
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+        }
 
-    #[allow(clippy::suspicious_arithmetic_impl)]
-    fn div(self, rhs: Ratio<D>) -> Self::Output {
-        self * rhs.inv()
+        // a terminating expansion ends in a cycle of remainder 0, which is all 0 digits
+        let repetend = if tortoise.is_zero() {
+            Vec::new()
+        } else {
+            (0..period)
+                .scan(tortoise, |remainder, _| {
+                    let next = digit(remainder);
+                    *remainder = step(remainder);
+                    Some(next)
+                })
+                .collect()
+        };
+
+        DecimalExpansion {
+            negative: sign == Sign::Minus,
+            integer_part: numerator / denominator,
+            prefix,
+            repetend,
+            radix,
+        }
     }
 }
-impl<D: PrimInt, O: PrimInt, N: PrimInt + Mul<D, Output = O> + Into<O>> Div<D> for Ratio<N> {
-    type Output = Ratio<O>;
 
-    #[allow(clippy::suspicious_arithmetic_impl)]
-    fn div(self, rhs: D) -> Self::Output {
-        Ratio {
-            numerator: self.numerator.into(),
-            denominator: self.denominator * rhs,
+/// The digits of a ratio in some radix, split into the integer part, the digits after the radix
+/// point that don't repeat, and the repetend that repeats forever after them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalExpansion {
+    pub negative: bool,
+    /// The integer part of the absolute value
+    pub integer_part: BigUint,
+    pub prefix: Vec<u32>,
+    /// Empty if the expansion terminates
+    pub repetend: Vec<u32>,
+    pub radix: u32,
+}
+
+impl Display for DecimalExpansion {
+    /// Writes the repetend in brackets, like -3.1(6) or 0.(142857)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digit =
+            |digit: &u32| char::from_digit(*digit, self.radix).expect("digits fit the radix");
+        if self.negative {
+            write!(f, "-")?;
         }
+        write!(f, "{}", self.integer_part.to_str_radix(self.radix))?;
+        if self.prefix.is_empty() && self.repetend.is_empty() {
+            return Ok(());
+        }
+        write!(f, ".{}", self.prefix.iter().map(digit).collect::<String>())?;
+        if !self.repetend.is_empty() {
+            write!(
+                f,
+                "({})",
+                self.repetend.iter().map(digit).collect::<String>()
+            )?;
+        }
+        Ok(())
     }
 }
-impl<D, N: PrimInt> DivAssign<D> for Ratio<N>
-where
-    Ratio<N>: Div<D, Output = Ratio<N>>,
-{
-    fn div_assign(&mut self, rhs: D) {
-        let result = *self / rhs;
-        self.numerator = result.numerator;
-        self.denominator = result.denominator;
+
+/// x 2^exponent, in steps that can't overflow the power of 2 on its own
+fn scale_by_power_of_two(x: f64, exponent: i64) -> f64 {
+    const STEP: i64 = 1000;
+    let (mut x, mut exponent) = (x, exponent);
+    while exponent > STEP {
+        x *= 2f64.powi(STEP as i32);
+        exponent -= STEP;
+    }
+    while exponent < -STEP {
+        x *= 2f64.powi(-STEP as i32);
+        exponent += STEP;
     }
+    x * 2f64.powi(exponent as i32)
 }
 
-impl<A: PrimInt, O: PrimInt, N: PrimInt + Add<O, Output = O> + Mul<A, Output = O>> Add<Ratio<A>>
-    for Ratio<N>
-{
-    type Output = Ratio<O>;
+/// The exact value of a finite f64 as numerator / denominator, where the denominator is a power
+/// of 2
+fn exact_f64(x: f64) -> (BigInt, BigInt) {
+    let bits = x.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7ff) as i64;
+    let mut mantissa = bits & ((1 << 52) - 1);
+    // subnormals don't have the implicit leading 1
+    let exponent = if biased_exponent == 0 {
+        -1074
+    } else {
+        mantissa |= 1 << 52;
+        biased_exponent - 1075
+    };
+    let mantissa = if x.is_sign_negative() {
+        -BigInt::from(mantissa)
+    } else {
+        BigInt::from(mantissa)
+    };
+    if exponent >= 0 {
+        (mantissa << exponent as u64, BigInt::one())
+    } else {
+        (mantissa, BigInt::one() << (-exponent) as u64)
+    }
+}
 
-    fn add(self, rhs: Ratio<A>) -> Self::Output {
-        Ratio {
-            numerator: self.numerator * rhs.denominator + self.denominator * rhs.numerator,
-            denominator: self.denominator * rhs.denominator,
+#[allow(dead_code)]
+impl<N: RatioInteger + TryFrom<BigInt>> Ratio<N> {
+    /// The first continued fraction convergent of x that converts back to exactly x, which is
+    /// the simple fraction that x was probably meant to be. This is None for infinities and NaN,
+    /// or if the fraction doesn't fit in N.
+    pub fn from_f64(x: f64) -> Option<Ratio<N>> {
+        if !x.is_finite() {
+            return None;
+        }
+        let (mut numerator, mut denominator) = exact_f64(x);
+        let (mut p_prev, mut p) = (BigInt::zero(), BigInt::one());
+        let (mut q_prev, mut q) = (BigInt::one(), BigInt::zero());
+        loop {
+            let (term, remainder) = floor_div_rem(numerator, denominator.clone());
+            (p_prev, p) = (p.clone(), &term * p + p_prev);
+            (q_prev, q) = (q.clone(), &term * q + q_prev);
+            let convergent = Ratio::new(p.clone(), q.clone());
+            // the last convergent is x itself, so this always stops
+            if remainder.is_zero() || convergent.to_f64() == x {
+                let (p, q) = convergent.into_parts();
+                return Some(Ratio::new(N::try_from(p).ok()?, N::try_from(q).ok()?));
+            }
+            (numerator, denominator) = (denominator, remainder);
         }
     }
 }
-impl<
-        MO,
-        A: PrimInt,
-        AO: PrimInt,
-        N: PrimInt + Mul<A, Output = MO> + Add<MO, Output = AO> + Into<AO>,
-    > Add<A> for Ratio<N>
-{
-    type Output = Ratio<AO>;
 
-    fn add(self, rhs: A) -> Self::Output {
-        Ratio {
-            numerator: self.numerator + self.denominator * rhs,
-            denominator: self.denominator.into(),
+impl<N: RatioInteger> PartialOrd for Ratio<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<N: RatioInteger> Ord for Ratio<N> {
+    /// Compares the continued fraction expansions term by term, so nothing is ever multiplied
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (mut a, mut b) = (self.numerator.clone(), self.denominator.clone());
+        let (mut c, mut d) = (other.numerator.clone(), other.denominator.clone());
+        // flips every time the comparison moves to the reciprocals of the fractional parts
+        let mut reversed = false;
+        loop {
+            let (first_floor, first_remainder) = floor_div_rem(a, b.clone());
+            let (second_floor, second_remainder) = floor_div_rem(c, d.clone());
+            let ordering = first_floor.cmp(&second_floor).then_with(|| {
+                match (first_remainder.is_zero(), second_remainder.is_zero()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) => Ordering::Equal,
+                }
+            });
+            if ordering != Ordering::Equal || first_remainder.is_zero() {
+                return if reversed {
+                    ordering.reverse()
+                } else {
+                    ordering
+                };
+            }
+            // r/b < s/d exactly when b/r > d/s
+            (a, b, c, d) = (b, first_remainder, d, second_remainder);
+            reversed = !reversed;
         }
     }
 }
-impl<A, N: PrimInt> AddAssign<A> for Ratio<N>
-where
-    Ratio<N>: Add<A, Output = Ratio<N>>,
-{
-    fn add_assign(&mut self, rhs: A) {
-        let result = *self + rhs;
-        self.numerator = result.numerator;
-        self.denominator = result.denominator;
+
+impl<N: RatioInteger + Display> Display for Ratio<N> {
+    /// Writes a/b, or just a for integers
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.denominator.is_one() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
     }
 }
 
-impl<S: PrimInt, O: PrimInt, N: PrimInt + Sub<O, Output = O> + Mul<S, Output = O>> Sub<Ratio<S>>
-    for Ratio<N>
-{
-    type Output = Ratio<O>;
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseRatioError {
+    /// Something other than digits where a number should be
+    InvalidNumber,
+    ZeroDenominator,
+    /// The value, or one of its parts, doesn't fit in the integer type
+    Overflow,
+}
 
-    fn sub(self, rhs: Ratio<S>) -> Self::Output {
-        Ratio {
-            numerator: self.numerator * rhs.denominator - self.denominator * rhs.numerator,
-            denominator: self.denominator * rhs.denominator,
-        }
+/// Parses an unsigned run of decimal digits
+fn parse_digits<N: RatioInteger>(digits: &str) -> Result<N, ParseRatioError> {
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(ParseRatioError::InvalidNumber);
     }
+    // with only digits, the only way left to fail is overflow
+    N::from_str_radix(digits, 10).map_err(|_| ParseRatioError::Overflow)
 }
-impl<
-        MO,
-        S: PrimInt,
-        SO: PrimInt,
-        N: PrimInt + Mul<S, Output = MO> + Sub<MO, Output = SO> + Into<SO>,
-    > Sub<S> for Ratio<N>
-{
-    type Output = Ratio<SO>;
 
-    fn sub(self, rhs: S) -> Self::Output {
-        Ratio {
-            numerator: self.numerator - self.denominator * rhs,
-            denominator: self.denominator.into(),
+/// Parses a nonnegative "a/b", "a.b" or "a"
+fn parse_unsigned<N: RatioInteger>(s: &str) -> Result<Ratio<N>, ParseRatioError> {
+    if let Some((numerator, denominator)) = s.split_once('/') {
+        let denominator: N = parse_digits(denominator)?;
+        if denominator.is_zero() {
+            return Err(ParseRatioError::ZeroDenominator);
         }
+        Ok(Ratio::new(parse_digits(numerator)?, denominator))
+    } else if let Some((whole, fraction)) = s.split_once('.') {
+        if fraction.is_empty() {
+            return Err(ParseRatioError::InvalidNumber);
+        }
+        // 12.34 is 1234 / 100, and the leading digits can be left off, as in .5
+        let numerator = parse_digits(&format!("{whole}{fraction}"))?;
+        let denominator = parse_digits(&format!("1{}", "0".repeat(fraction.len())))?;
+        Ok(Ratio::new(numerator, denominator))
+    } else {
+        Ok(Ratio::new_int(parse_digits(s)?))
     }
 }
-impl<S, N: PrimInt> SubAssign<S> for Ratio<N>
-where
-    Ratio<N>: Sub<S, Output = Ratio<N>>,
-{
-    fn sub_assign(&mut self, rhs: S) {
-        let result = *self - rhs;
-        self.numerator = result.numerator;
-        self.denominator = result.denominator;
+
+impl<N: RatioInteger> FromStr for Ratio<N> {
+    type Err = ParseRatioError;
+
+    /// Parses fractions like "-3/7", mixed numbers like "2 1/3" and decimals like "-0.125"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        let magnitude = match s.split_once(' ') {
+            Some((whole, fraction)) => {
+                // the part after the whole number has to be a proper fraction
+                let fraction = fraction.trim_start();
+                if !fraction.contains('/') {
+                    return Err(ParseRatioError::InvalidNumber);
+                }
+                let fraction: Ratio<N> = parse_unsigned(fraction)?;
+                if fraction.numerator >= fraction.denominator {
+                    return Err(ParseRatioError::InvalidNumber);
+                }
+                Ratio::new_int(parse_digits(whole)?)
+                    .checked_add(&fraction)
+                    .ok_or(ParseRatioError::Overflow)?
+            }
+            None => parse_unsigned(s)?,
+        };
+
+        if negative {
+            Ratio::zero()
+                .checked_sub(&magnitude)
+                .ok_or(ParseRatioError::Overflow)
+        } else {
+            Ok(magnitude)
+        }
     }
 }
 
 pub trait PiecewiseAdd<Rhs> {
     type Output;
 
+    /// Adds the numerators and the denominators, which gives the mediant of two fractions
     #[allow(dead_code)]
     fn piecewise_add(self, rhs: Rhs) -> Self::Output;
 }
-impl<A: PrimInt, O: PrimInt, N: PrimInt + Add<A, Output = O>> PiecewiseAdd<Ratio<A>> for Ratio<N> {
-    type Output = Ratio<O>;
+impl<N: RatioInteger> PiecewiseAdd<Ratio<N>> for Ratio<N> {
+    type Output = Ratio<N>;
 
-    fn piecewise_add(self, rhs: Ratio<A>) -> Self::Output {
-        Ratio {
-            numerator: self.numerator + rhs.numerator,
-            denominator: self.denominator + rhs.denominator,
-        }
+    fn piecewise_add(self, rhs: Ratio<N>) -> Self::Output {
+        Ratio::new(
+            self.numerator + rhs.numerator,
+            self.denominator + rhs.denominator,
+        )
     }
 }
 
@@ -222,49 +646,35 @@ pub trait SignedSimplifiable {
     fn signed_simplify(self, primes: &Primes) -> Ratio<i64>;
 }
 
+// Every Ratio is already in lowest terms, but these are kept for existing callers
 impl<N: PrimInt + Into<u64> + Unsigned> Simplifiable for Ratio<N> {
-    fn simplify(self, primes: &Primes) -> Ratio<u64> {
-        let mut reduced_num: u64 = self.numerator.into();
-        let mut reduced_denom: u64 = self.denominator.into();
-
-        primes.find_gcd_and_reduce(&mut reduced_num, &mut reduced_denom);
-
-        Ratio {
-            numerator: reduced_num,
-            denominator: reduced_denom,
-        }
+    fn simplify(self, _primes: &Primes) -> Ratio<u64> {
+        let (numerator, denominator) = self.into_parts();
+        Ratio::new(numerator.into(), denominator.into())
     }
 }
 impl<N: PrimInt + Into<i64> + Signed> SignedSimplifiable for Ratio<N> {
-    fn signed_simplify(self, primes: &Primes) -> Ratio<i64> {
-        let mut reduced_num: i64 = self.numerator.into();
-        let mut reduced_denom: i64 = self.denominator.into();
-
-        primes.find_gcd_and_reduce_signed(&mut reduced_num, &mut reduced_denom);
-
-        Ratio {
-            numerator: reduced_num,
-            denominator: reduced_denom,
-        }
-    }
-}
-
-impl<N: PrimInt> PartialEq for Ratio<N> {
-    fn eq(&self, other: &Self) -> bool {
-        (self.numerator == other.numerator && self.denominator == other.denominator)
-            || self.numerator * other.denominator == other.numerator * self.denominator
+    fn signed_simplify(self, _primes: &Primes) -> Ratio<i64> {
+        let (numerator, denominator) = self.into_parts();
+        Ratio::new(numerator.into(), denominator.into())
     }
 }
 
 mod test_ratio {
     #[allow(unused_imports)]
-    use super::{Ratio, SignedSimplifiable, Simplifiable};
+    use super::{
+        DecimalExpansion, ParseRatioError, PiecewiseAdd, Ratio, SignedSimplifiable, Simplifiable,
+    };
+    #[allow(unused_imports)]
+    use crate::euler_tools::arbitrary_precision::rational_digits;
     #[allow(unused_imports)]
     use crate::euler_tools::prime_finder::Primes;
     #[allow(unused_imports)]
-    use num_traits::{Inv, One, PrimInt, Signed, Unsigned};
+    use num_bigint::{BigInt, BigUint};
     #[allow(unused_imports)]
-    use std::ops::{Add, Div, Mul, Neg, Sub};
+    use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Inv, One, Pow, Zero};
+    #[allow(unused_imports)]
+    use std::collections::HashSet;
 
     #[test]
     fn test_neg() {
@@ -273,6 +683,7 @@ mod test_ratio {
     #[test]
     fn test_inv() {
         assert_eq!(Ratio::new(1, 2).inv(), Ratio::new(2, 1));
+        assert_eq!(Ratio::new(-1, 2).inv(), Ratio::new(2, -1));
     }
 
     #[test]
@@ -311,20 +722,337 @@ mod test_ratio {
         assert_eq!(Ratio::new(2, 3) - 5, Ratio::new(-13, 3));
     }
 
+    #[test]
+    fn test_assign() {
+        let mut ratio = Ratio::new(1u64, 2);
+        ratio += Ratio::new(1, 3);
+        ratio *= 6;
+        ratio -= 1;
+        ratio /= Ratio::new(8, 3);
+        assert_eq!(ratio, Ratio::new(3, 2));
+    }
+
     #[test]
     fn test_eq() {
         assert_eq!(Ratio::new(1, 2), Ratio::new(1, 2));
         assert!(Ratio::new(1, 2) == Ratio::new(5, 10));
     }
 
+    #[test]
+    fn test_normalized_on_construction() {
+        let reduced = Ratio::new(12u32, 18u32);
+        assert_eq!((*reduced.numerator(), *reduced.denominator()), (2, 3));
+
+        let reduced = Ratio::new(12i32, -18i32);
+        assert_eq!(reduced.into_parts(), (-2, 3));
+
+        let reduced = Ratio::new(0i64, -5i64);
+        assert_eq!(reduced.into_parts(), (0, 1));
+
+        // results come out reduced too
+        let sum = Ratio::new(1u64, 6) + Ratio::new(1, 3);
+        assert_eq!(sum.into_parts(), (1, 2));
+        let product = Ratio::new(4i64, 9) * Ratio::new(-3, 8);
+        assert_eq!(product.into_parts(), (-1, 6));
+        let zero = 0;
+        assert_eq!((Ratio::new(1, 2) * zero).into_parts(), (0, 1));
+        assert_eq!(
+            Ratio::new(1, 3)
+                .piecewise_add(Ratio::new(1, 1))
+                .into_parts(),
+            (1, 2)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "denominator must be nonzero")]
+    fn test_zero_denominator() {
+        Ratio::new(1, 0);
+    }
+
+    #[test]
+    fn test_checked() {
+        // the cross multiplications overflow, but the reduced answers fit
+        let big = Ratio::new(u64::MAX - 1, u64::MAX);
+        assert_eq!(big.checked_mul(&big.inv()), Some(Ratio::one()));
+        let (third, fifth) = (Ratio::new(1u64, 3 << 40), Ratio::new(1, 5 << 40));
+        assert_eq!(third.checked_add(&fifth), Some(Ratio::new(1, 15 << 37)));
+
+        assert_eq!(Ratio::new(u64::MAX, 1).checked_add(&Ratio::one()), None);
+        assert_eq!(Ratio::new(1, u64::MAX).checked_mul(&Ratio::new(1, 2)), None);
+        assert_eq!(Ratio::new(1u8, 3).checked_sub(&Ratio::new(1, 2)), None);
+        assert_eq!(Ratio::new(1u8, 3).checked_div(&Ratio::zero()), None);
+        assert_eq!(
+            Ratio::new(1i8, 2).checked_sub(&Ratio::new(1, 2)),
+            Some(Ratio::zero())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Ratio addition overflowed")]
+    fn test_overflow_panics() {
+        let _ = Ratio::new(u8::MAX, 1) + 1;
+    }
+
+    #[test]
+    fn test_big_ratios() {
+        // 1 + 1/2 + ... + 1/50 grows a denominator too big for a u64
+        let harmonic = (1..=50u32).fold(Ratio::<BigUint>::zero(), |sum, n| {
+            sum + Ratio::new(BigUint::one(), BigUint::from(n))
+        });
+        assert_eq!(harmonic.denominator().to_string(), "3099044504245996706400");
+        assert_eq!(harmonic.numerator().to_string(), "13943237577224054960759");
+
+        let signed = Ratio::new(BigInt::from(6), BigInt::from(-4));
+        assert_eq!(
+            signed.clone().into_parts(),
+            (BigInt::from(-3), BigInt::from(2))
+        );
+        assert_eq!(-signed * BigInt::from(2), Ratio::new_int(BigInt::from(3)));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let fractions: Vec<Ratio<i64>> = (-12..=12)
+            .flat_map(|p| (1..=12).map(move |q| Ratio::new(p, q)))
+            .collect();
+        for a in &fractions {
+            for b in &fractions {
+                let (p, q) = (*a.numerator() as i128, *a.denominator() as i128);
+                let (r, s) = (*b.numerator() as i128, *b.denominator() as i128);
+                assert_eq!(a.cmp(b), (p * s).cmp(&(r * q)), "{a} vs {b}");
+            }
+        }
+
+        // the cross products here would overflow a u64
+        let max = u64::MAX;
+        assert!(Ratio::new(max - 1, max) > Ratio::new(max - 2, max - 1));
+        assert!(Ratio::new(1, max) < Ratio::new(1, max - 1));
+        assert!(Ratio::new(i64::MIN + 2, i64::MAX) < Ratio::new(i64::MIN + 3, i64::MAX - 1));
+
+        let mut sorted = vec![
+            Ratio::new(3, 7),
+            Ratio::new(-1, 2),
+            Ratio::new(2, 5),
+            Ratio::zero(),
+        ];
+        sorted.sort();
+        assert_eq!(
+            sorted,
+            [
+                Ratio::new(-1, 2),
+                Ratio::zero(),
+                Ratio::new(2, 5),
+                Ratio::new(3, 7)
+            ]
+        );
+
+        let distinct: HashSet<Ratio<u32>> = (1..=4)
+            .flat_map(|q| (0..=q).map(move |p| Ratio::new(p, q)))
+            .collect();
+        // 0, 1/4, 1/3, 1/2, 2/3, 3/4, 1
+        assert_eq!(distinct.len(), 7);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let cases = [
+            ((7, 2), (3, 4, 4)),
+            ((-7, 2), (-4, -3, -4)),
+            ((5, 3), (1, 2, 2)),
+            ((-5, 3), (-2, -1, -2)),
+            ((4, 3), (1, 2, 1)),
+            ((-4, 3), (-2, -1, -1)),
+            ((6, 3), (2, 2, 2)),
+        ];
+        for ((p, q), expected) in cases {
+            let ratio = Ratio::new(p, q);
+            assert_eq!(
+                (ratio.floor(), ratio.ceil(), ratio.round()),
+                expected,
+                "{ratio}"
+            );
+        }
+        assert_eq!(Ratio::new(u8::MAX, 2).round(), 128);
+    }
+
+    #[test]
+    fn test_display_and_parse() {
+        assert_eq!(Ratio::new(6, -14).to_string(), "-3/7");
+        assert_eq!(Ratio::new(8, 4).to_string(), "2");
+
+        let parse = |s: &str| s.parse::<Ratio<i64>>();
+        assert_eq!(parse("-3/7"), Ok(Ratio::new(-3, 7)));
+        assert_eq!(parse(" 6/4 "), Ok(Ratio::new(3, 2)));
+        assert_eq!(parse("2 1/3"), Ok(Ratio::new(7, 3)));
+        assert_eq!(parse("-2 1/3"), Ok(Ratio::new(-7, 3)));
+        assert_eq!(parse("0.125"), Ok(Ratio::new(1, 8)));
+        assert_eq!(parse("-12.50"), Ok(Ratio::new(-25, 2)));
+        assert_eq!(parse(".5"), Ok(Ratio::new(1, 2)));
+        assert_eq!(parse("+17"), Ok(Ratio::new_int(17)));
+
+        assert_eq!(parse("1/0"), Err(ParseRatioError::ZeroDenominator));
+        assert_eq!(parse("1/-2"), Err(ParseRatioError::InvalidNumber));
+        assert_eq!(parse("2 4/3"), Err(ParseRatioError::InvalidNumber));
+        assert_eq!(parse("2 3"), Err(ParseRatioError::InvalidNumber));
+        assert_eq!(parse("1."), Err(ParseRatioError::InvalidNumber));
+        assert_eq!(parse("a/b"), Err(ParseRatioError::InvalidNumber));
+        assert_eq!(parse(""), Err(ParseRatioError::InvalidNumber));
+        assert_eq!(
+            parse("0.00000000000000000001"),
+            Err(ParseRatioError::Overflow)
+        );
+        assert_eq!("-1/2".parse::<Ratio<u64>>(), Err(ParseRatioError::Overflow));
+        assert_eq!("-0".parse::<Ratio<u64>>(), Ok(Ratio::zero()));
+
+        let big: Ratio<BigUint> = "123456789012345678901234567890/3".parse().unwrap();
+        assert_eq!(big.to_string(), "41152263004115226300411522630");
+        for ratio in [Ratio::new(-3, 7), Ratio::new(22, 7), Ratio::zero()] {
+            assert_eq!(parse(&ratio.to_string()), Ok(ratio));
+        }
+    }
+
+    #[test]
+    fn test_to_f64() {
+        // IEEE division is correctly rounded, so the two have to agree exactly
+        for p in [1i64, 2, 7, 22, 355, -1, -99, 123_456_789, (1 << 53) - 1] {
+            for q in [1i64, 3, 7, 10, 113, 1 << 20, 999_999_937] {
+                assert_eq!(Ratio::new(p, q).to_f64(), p as f64 / q as f64, "{p}/{q}");
+            }
+        }
+        assert_eq!(Ratio::new(u64::MAX, 1).to_f64(), u64::MAX as f64);
+        assert_eq!(Ratio::<i32>::zero().to_f64(), 0.0);
+
+        // 2^1100 / 3^600 is about 0.0007, but neither part fits in an f64
+        let big = Ratio::new(BigUint::one() << 1100u32, BigUint::from(3u8).pow(600u32));
+        let expected = (1100.0 * 2f64.ln() - 600.0 * 3f64.ln()).exp();
+        assert!((big.to_f64() / expected - 1.0).abs() < 1e-9);
+        let huge = Ratio::new_int(BigUint::one() << 1024u32);
+        assert_eq!(huge.to_f64(), f64::INFINITY);
+        let tiny = Ratio::new(BigUint::one(), BigUint::one() << 1074u32);
+        assert_eq!(tiny.to_f64(), f64::from_bits(1));
+        // half of the smallest subnormal rounds to the even 0, but anything more rounds up
+        let half_tiny = Ratio::new(BigUint::one(), BigUint::one() << 1075u32);
+        assert_eq!(half_tiny.to_f64(), 0.0);
+        let over_half_tiny = Ratio::new(BigUint::from(3u8), BigUint::one() << 1076u32);
+        assert_eq!(over_half_tiny.to_f64(), f64::from_bits(1));
+
+        // around f64::MIN_POSITIVE, m 2^-1074 has the bit pattern m whether it's subnormal or
+        // not, so the points between neighbours have to round to the nearest or the even one
+        let power = |exponent: u32| BigUint::one() << exponent;
+        for m in (1u64 << 52) - 4..(1 << 52) + 4 {
+            let halfway = Ratio::new(BigUint::from(2 * m + 1), power(1075));
+            assert_eq!(halfway.to_f64(), f64::from_bits(m + (m & 1)), "{m} + 1/2");
+            let below = Ratio::new(BigUint::from(4 * m + 1), power(1076));
+            assert_eq!(below.to_f64(), f64::from_bits(m), "{m} + 1/4");
+            let above = Ratio::new(BigUint::from(4 * m + 3), power(1076));
+            assert_eq!(above.to_f64(), f64::from_bits(m + 1), "{m} + 3/4");
+        }
+        for q in [3u64, 5, 7, 10, 113, 999_999_937] {
+            let ratio = Ratio::new(BigUint::one(), BigUint::from(q) << 1022u32);
+            assert_eq!(
+                ratio.to_f64(),
+                f64::MIN_POSITIVE / q as f64,
+                "MIN_POSITIVE / {q}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(Ratio::from_f64(0.1), Some(Ratio::new(1i64, 10)));
+        assert_eq!(Ratio::from_f64(1.0 / 3.0), Some(Ratio::new(1i64, 3)));
+        assert_eq!(Ratio::from_f64(-2.5), Some(Ratio::new(-5i64, 2)));
+        assert_eq!(Ratio::from_f64(0.0), Some(Ratio::<u8>::zero()));
+        assert_eq!(
+            Ratio::from_f64(std::f64::consts::PI),
+            Some(Ratio::new(245_850_922u64, 78_256_779))
+        );
+
+        assert_eq!(Ratio::<i64>::from_f64(f64::NAN), None);
+        assert_eq!(Ratio::<i64>::from_f64(f64::INFINITY), None);
+        assert_eq!(Ratio::<u64>::from_f64(-0.5), None);
+        assert_eq!(Ratio::<u64>::from_f64(1e300), None);
+        let big = Ratio::<BigInt>::from_f64(1e300).unwrap();
+        assert_eq!(big.to_f64(), 1e300);
+
+        for x in [
+            0.3,
+            2.0 / 7.0,
+            1e-10,
+            123.456,
+            -9.75,
+            f64::MIN_POSITIVE,
+            5e-324,
+        ] {
+            assert_eq!(Ratio::<BigInt>::from_f64(x).unwrap().to_f64(), x, "{x}");
+        }
+    }
+
+    #[test]
+    fn test_decimal_expansion() {
+        let render =
+            |p: i64, q: i64, radix: u32| Ratio::new(p, q).to_decimal_expansion(radix).to_string();
+        assert_eq!(render(1, 7, 10), "0.(142857)");
+        assert_eq!(render(1, 6, 10), "0.1(6)");
+        assert_eq!(render(-19, 6, 10), "-3.1(6)");
+        assert_eq!(render(1, 2, 10), "0.5");
+        assert_eq!(render(1, 9, 10), "0.(1)");
+        assert_eq!(render(1, 4, 10), "0.25");
+        assert_eq!(render(7, 1, 10), "7");
+        assert_eq!(render(-1, 3, 3), "-0.1");
+        assert_eq!(render(1, 10, 2), "0.0(0011)");
+        assert_eq!(render(1000, 37, 16), "1b.(06eb3e453)");
+
+        let expansion = Ratio::new(1u32, 12).to_decimal_expansion(10);
+        assert_eq!(
+            expansion,
+            DecimalExpansion {
+                negative: false,
+                integer_part: BigUint::zero(),
+                prefix: vec![0, 8],
+                repetend: vec![3],
+                radix: 10,
+            }
+        );
+
+        // the expansion has to match plain long division
+        for radix in [2, 7, 10, 12] {
+            for q in 1..300u64 {
+                for p in [1, q / 3 + 1, 5 * q + 2] {
+                    let expansion = Ratio::new(p, q).to_decimal_expansion(radix);
+                    let digits = rational_digits(&BigUint::from(p), &BigUint::from(q), radix);
+                    let integer_digits = digits.integer_digit_count();
+                    let length = expansion.prefix.len() + 2 * expansion.repetend.len() + 3;
+                    let fraction: Vec<u32> = digits.skip(integer_digits).take(length).collect();
+                    let expected: Vec<u32> = expansion
+                        .prefix
+                        .iter()
+                        .chain(expansion.repetend.iter().cycle())
+                        .copied()
+                        .chain(std::iter::repeat(0))
+                        .take(length)
+                        .collect();
+                    assert_eq!(fraction, expected, "{p}/{q} in base {radix}");
+                    assert_eq!(expansion.integer_part, BigUint::from(p / q));
+                }
+            }
+        }
+
+        // 1/983 has the longest period of any 1/d below 1000
+        let expansion = Ratio::new(BigUint::one(), BigUint::from(983u32)).to_decimal_expansion(10);
+        assert!(expansion.prefix.is_empty());
+        assert_eq!(expansion.repetend.len(), 982);
+    }
+
     #[test]
     fn test_unsigned_reduce() {
         let primes = Primes::find_primes(20);
         let orig_ratio = Ratio::new(5u64, 10u64);
         let reduced = orig_ratio.simplify(&primes);
         assert!(reduced == orig_ratio);
-        assert_eq!(reduced.numerator, 1);
-        assert_eq!(reduced.denominator, 2);
+        assert_eq!(*reduced.numerator(), 1);
+        assert_eq!(*reduced.denominator(), 2);
     }
 
     #[test]
@@ -1,6 +1,6 @@
 // https://projecteuler.net/problem=4
 
-use crate::euler_tools;
+use crate::euler_tools::palindromes::palindromes_descending;
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -11,24 +11,19 @@ pub fn make() -> crate::Problem {
 }
 
 fn core_solve(digits: u8) -> i64 {
-    let mut biggest = 0i64;
-    let min = 10i64.pow((digits - 1) as u32);
+    let min = 10u64.pow((digits - 1) as u32);
     let max = 10 * min - 1;
 
-    let mut min_b = min;
-
-    for a in (min_b..=max).rev() {
-        for b in (min_b..=a).rev() {
-            let product = a * b;
-            if product > biggest && euler_tools::is_palindrome(&product.to_string()) {
-                biggest = product;
-                min_b = b + 1;
-                break;
-            }
-        }
-    }
-
-    biggest
+    // a palindrome is a product when it has a factor between its square root and max whose
+    // cofactor is still big enough
+    palindromes_descending(10, max * max)
+        .find(|palindrome| {
+            (min..=max)
+                .rev()
+                .take_while(|a| a * a >= *palindrome)
+                .any(|a| palindrome % a == 0 && palindrome / a >= min)
+        })
+        .expect("some palindrome is a product") as i64
 }
 
 #[cfg(test)]
@@ -10,12 +10,9 @@ pub fn make() -> crate::Problem {
     }
 }
 
-fn core_solve(limit: usize) -> i64 {
-    prime_finder::Primes::find_primes(limit)
-        .prime_iterator()
-        // convert to u64 to prevent overflow and allow sum to work
-        .map(|p| *p as i64)
-        .sum()
+fn core_solve(limit: u64) -> i64 {
+    // the problem asks for primes strictly below the limit
+    prime_finder::prime_sum(limit.saturating_sub(1)) as i64
 }
 
 #[cfg(test)]
@@ -25,6 +22,13 @@ mod tests {
         assert_eq!(super::core_solve(10), 17)
     }
 
+    #[test]
+    fn no_primes_below_small_limits() {
+        assert_eq!(super::core_solve(0), 0);
+        assert_eq!(super::core_solve(2), 0);
+        assert_eq!(super::core_solve(3), 2);
+    }
+
     #[test]
     fn verify_answer() {
         assert_eq!((super::make().solve)(), 142913828922)
@@ -1,5 +1,7 @@
 // https://projecteuler.net/problem=15
 
+use crate::euler_tools::combinatorics::binomial;
+
 pub fn make() -> crate::Problem {
     crate::Problem {
         title: "Lattice Paths",
@@ -8,28 +10,9 @@ pub fn make() -> crate::Problem {
     }
 }
 
-#[allow(clippy::needless_range_loop)]
-fn core_solve(grid_size: usize) -> i64 {
-    let grid_points = grid_size + 1;
-    let mut cache: Vec<i64> = vec![0; grid_points * grid_points];
-
-    // setup boundary conidtions
-    // bottom of grid
-    for index in (grid_points - 1) * grid_points..grid_points * grid_points {
-        cache[index] = 1
-    }
-    // right of grid
-    for index in (grid_points - 1..grid_points * grid_points).step_by(grid_points) {
-        cache[index] = 1
-    }
-
-    for row in (0..grid_points - 1).rev() {
-        for index in (grid_points * row..grid_points * (row + 1) - 1).rev() {
-            cache[index] = cache[index + grid_points] + cache[index + 1];
-        }
-    }
-
-    cache[0]
+fn core_solve(grid_size: i64) -> i64 {
+    // each path is a choice of which grid_size of its 2 grid_size steps go down
+    binomial(2 * grid_size, grid_size).expect("the answer fits in an i64")
 }
 
 #[cfg(test)]
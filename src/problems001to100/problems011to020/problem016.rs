@@ -2,6 +2,8 @@
 
 use num_bigint::BigUint;
 
+use crate::euler_tools::DigitIterator;
+
 pub fn make() -> crate::Problem {
     crate::Problem {
         title: "Power Digit Sum",
@@ -13,12 +15,9 @@ pub fn make() -> crate::Problem {
 fn core_solve(exponent: u32) -> i64 {
     let large_number = BigUint::from(2usize);
 
-    large_number
-        .pow(exponent)
-        .to_str_radix(10)
-        .as_bytes()
-        .iter()
-        .fold(0, |total, digit| total + (digit - b'0') as i64)
+    DigitIterator::<BigUint>::new(large_number.pow(exponent))
+        .map(i64::from)
+        .sum()
 }
 
 #[cfg(test)]
@@ -2,7 +2,7 @@
 
 use num_bigint::BigUint;
 
-use crate::euler_tools;
+use crate::euler_tools::{self, DigitIterator};
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -13,11 +13,8 @@ pub fn make() -> crate::Problem {
 }
 
 fn core_solve(digits: u8) -> i64 {
-    euler_tools::big_factorial(BigUint::from(digits))
-        .to_string()
-        .as_bytes()
-        .iter()
-        .map(|n| i64::from(n - b'0'))
+    DigitIterator::<BigUint>::new(euler_tools::big_factorial(BigUint::from(digits)))
+        .map(i64::from)
         .sum()
 }
 
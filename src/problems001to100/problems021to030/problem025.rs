@@ -18,7 +18,6 @@ pub fn make() -> crate::Problem {
 #[allow(dead_code)]
 fn core_solve_slow(digits: usize) -> i64 {
     let limit = &BigUint::pow(&BigUint::ten(), digits as u32 - 1);
-    println!("{}", limit.to_string().len());
     for (fibb_num, fibb) in fibonacci_iterator::<BigUint>().enumerate() {
         if fibb.cmp(limit) != Ordering::Less {
             return fibb_num as i64;
@@ -1,6 +1,10 @@
 // https://projecteuler.net/problem=26
 
-use crate::euler_tools::prime_finder::Primes;
+use std::cmp::Reverse;
+
+use crate::euler_tools::{
+    modular::multiplicative_group::multiplicative_order, prime_finder::Primes,
+};
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -11,43 +15,14 @@ pub fn make() -> crate::Problem {
 }
 
 fn core_solve(max_denom: usize) -> i64 {
-    let mut longest_length = 1;
-    let mut longest_denom = 1;
-
     let primes = Primes::find_primes(max_denom);
 
-    let mut remainders: Vec<u32> = Vec::new();
-
-    for denom in primes.prime_iterator() {
-        remainders.clear();
-        let mut n = 10;
-        remainders.push(1);
-
-        let mut remainder_looped = false;
-        while !remainder_looped {
-            n %= denom;
-            if n == 0 {
-                break;
-            }
-
-            for (i, remainder) in remainders.iter().enumerate() {
-                if *remainder == n {
-                    remainder_looped = true;
-                    let length = remainders.len() - i;
-                    if longest_length < length {
-                        longest_denom = *denom;
-                        longest_length = length;
-                    }
-                    break;
-                }
-            }
-
-            remainders.push(n);
-            n *= 10;
-        }
-    }
-
-    longest_denom as i64
+    // 1/p repeats every ord_p(10) digits, and composite denominators never beat their prime factors
+    primes
+        .prime_iterator()
+        .filter_map(|p| Some((multiplicative_order(10, *p as u64, &primes)?, *p)))
+        .max_by_key(|(length, p)| (*length, Reverse(*p)))
+        .map_or(1, |(_, p)| p as i64)
 }
 
 #[cfg(test)]
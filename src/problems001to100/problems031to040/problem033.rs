@@ -4,10 +4,7 @@ use num_traits::One;
 use strum::EnumCount;
 use strum_macros::{EnumCount, FromRepr};
 
-use crate::euler_tools::{
-    prime_finder::Primes,
-    ratio::{PiecewiseAdd, Ratio, Simplifiable},
-};
+use crate::euler_tools::ratio::Ratio;
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -23,14 +20,14 @@ fn iterate_fractions() -> FractionIterator {
     FractionIterator {
         reducible_position: ReducePosition::first(),
         reducible_digit: 1,
-        non_reducible_ratio: Ratio::new(1, 2),
+        non_reducible_digits: (1, 2),
     }
      */
     // When we call next, this state will update and return that desired position
     FractionIterator {
         reducible_position: ReducePosition::last(),
         reducible_digit: 9,
-        non_reducible_ratio: Ratio::new(1, 1),
+        non_reducible_digits: (1, 1),
     }
 }
 
@@ -59,7 +56,8 @@ impl ReducePosition {
 struct FractionIterator {
     reducible_position: ReducePosition,
     reducible_digit: u64,
-    non_reducible_ratio: Ratio<u64>,
+    /// the numerator and denominator digits that are left after cancelling
+    non_reducible_digits: (u64, u64),
 }
 
 impl Iterator for FractionIterator {
@@ -73,14 +71,13 @@ impl Iterator for FractionIterator {
         state_ready = state_ready || self.update_check_numerator();
 
         if state_ready {
-            let (reduce_mult, non_reduce_mult) = get_ratio_multipliers(self.reducible_position);
-            let reducible_part =
-                Ratio::new(self.reducible_digit, self.reducible_digit) * reduce_mult;
-            let non_reduc_part = self.non_reducible_ratio * non_reduce_mult;
-            Some((
-                reducible_part.piecewise_add(non_reduc_part),
-                self.non_reducible_ratio,
-            ))
+            let (reduce_mult, non_reduce_mult) = get_digit_multipliers(self.reducible_position);
+            let (numerator_digit, denominator_digit) = self.non_reducible_digits;
+            let big_fraction = Ratio::new(
+                self.reducible_digit * reduce_mult.0 + numerator_digit * non_reduce_mult.0,
+                self.reducible_digit * reduce_mult.1 + denominator_digit * non_reduce_mult.1,
+            );
+            Some((big_fraction, Ratio::new(numerator_digit, denominator_digit)))
         } else {
             None
         }
@@ -97,12 +94,14 @@ fn update_check_digit(digit: &mut u64, reset_digit: u64) -> bool {
     }
 }
 
-fn get_ratio_multipliers(reducible_position: ReducePosition) -> (Ratio<u64>, Ratio<u64>) {
+/// The place values of the (numerator, denominator) digits for the cancelled digit and the ones
+/// that are left
+fn get_digit_multipliers(reducible_position: ReducePosition) -> ((u64, u64), (u64, u64)) {
     match reducible_position {
-        ReducePosition::FirstFirst => (Ratio::new(10, 10), Ratio::new(1, 1)),
-        ReducePosition::FirstSecond => (Ratio::new(10, 1), Ratio::new(1, 10)),
-        ReducePosition::SecondFirst => (Ratio::new(1, 10), Ratio::new(10, 1)),
-        ReducePosition::SecondSecond => (Ratio::new(1, 1), Ratio::new(10, 10)),
+        ReducePosition::FirstFirst => ((10, 10), (1, 1)),
+        ReducePosition::FirstSecond => ((10, 1), (1, 10)),
+        ReducePosition::SecondFirst => ((1, 10), (10, 1)),
+        ReducePosition::SecondSecond => ((1, 1), (10, 10)),
     }
 }
 
@@ -118,7 +117,7 @@ impl FractionIterator {
     }
 
     fn update_check_numerator(&mut self) -> bool {
-        update_check_digit(&mut self.non_reducible_ratio.numerator, 1)
+        update_check_digit(&mut self.non_reducible_digits.0, 1)
     }
 
     fn update_check_denominator(&mut self) -> bool {
@@ -130,8 +129,8 @@ impl FractionIterator {
         // but if we have to wrap back around, the numerator will increment as well,
         // so we want to have denominator >= new numerator + 1 = (current numerator + 1) + 1
         update_check_digit(
-            &mut self.non_reducible_ratio.denominator,
-            self.non_reducible_ratio.numerator + 2,
+            &mut self.non_reducible_digits.1,
+            self.non_reducible_digits.0 + 2,
         )
     }
 }
@@ -152,13 +151,8 @@ fn core_solve() -> i64 {
         }
     }
 
-    // We know all of the composite fractions simplify to 1-digit numbers.
-    let primes = Primes::find_primes(10);
-    fraction_product
-        .simplify(&primes)
-        .denominator
-        .try_into()
-        .unwrap()
+    // the product is kept in lowest terms as it goes
+    (*fraction_product.denominator()).try_into().unwrap()
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 // https://projecteuler.net/problem=36
 
-use crate::euler_tools;
+use crate::euler_tools::palindromes::{Palindrome, palindromes};
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -11,12 +11,10 @@ pub fn make() -> crate::Problem {
 }
 
 fn core_solve() -> i64 {
-    (1usize..1_000_000)
-        .filter(|n| {
-            // faster to do base-2 first because bit manipulation/a single equality check is way faster than the other approach
-            euler_tools::is_bin_palindrome(*n) && euler_tools::is_palindrome(&n.to_string())
-        })
-        .sum::<usize>() as i64
+    // only the decimal palindromes get generated, so there's far fewer binary ones to test
+    palindromes(10, 999_999)
+        .filter(|n| n.is_palindrome_radix(2))
+        .sum::<u64>() as i64
 }
 
 #[cfg(test)]
@@ -1,9 +1,6 @@
 // https://projecteuler.net/problem=48
 
-use std::ops::Add;
-
-use num_bigint::BigUint;
-use num_traits::One;
+use crate::euler_tools::modular::ModInt;
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -13,20 +10,12 @@ pub fn make() -> crate::Problem {
     }
 }
 
-fn core_solve(last_num: u32) -> i64 {
-    let mut sum = BigUint::ZERO;
-
-    let one = BigUint::one();
-    let mut big_n = BigUint::one();
-    for n in 1..=last_num {
-        sum = sum.add(big_n.pow(n));
-
-        assert_eq!(big_n, BigUint::from(n));
-        big_n = big_n.add(&one);
-    }
-
-    let string_sum = sum.to_string();
-    string_sum[string_sum.len() - 10..].parse().unwrap()
+fn core_solve(last_num: u64) -> i64 {
+    // we only care about the last ten digits
+    (1..=last_num)
+        .map(|n| ModInt::<10_000_000_000>::new(n).pow(n))
+        .sum::<ModInt<10_000_000_000>>()
+        .value() as i64
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 // https://projecteuler.net/problem=52
 
-use crate::euler_tools::DigitIterator;
+use crate::euler_tools::digit_signature::DigitSignature;
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -15,20 +15,9 @@ fn core_solve(highest_multiple: i64) -> i64 {
         let largest_base_value = 10i64.pow(digit_length + 1) / highest_multiple;
 
         for base_value in 10i64.pow(digit_length)..=largest_base_value {
-            let mut all_values_match = true;
-            let base_set = DigitIterator::<i64>::create_digit_set(base_value);
-            for multiple in 2..=highest_multiple {
-                let product = base_value * multiple;
-
-                if !DigitIterator::<i64>::create_digit_set(product)
-                    .iter()
-                    .zip(base_set.iter())
-                    .all(|(a, b)| a == b)
-                {
-                    all_values_match = false;
-                    break;
-                }
-            }
+            let base_signature = DigitSignature::new(base_value);
+            let all_values_match = (2..=highest_multiple)
+                .all(|multiple| DigitSignature::new(base_value * multiple) == base_signature);
 
             if all_values_match {
                 return base_value;
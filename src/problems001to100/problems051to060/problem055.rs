@@ -1,11 +1,9 @@
 // https://projecteuler.net/problem=55
 
-use std::str::FromStr;
-
 use num_bigint::BigUint;
 use num_traits::FromPrimitive;
 
-use crate::euler_tools::is_palindrome;
+use crate::euler_tools::palindromes::Palindrome;
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -44,10 +42,7 @@ fn compute_is_lychrel_core(
     depth: usize,
     big_limit: &BigUint,
 ) -> bool {
-    let reversed_n_str = unsafe {
-        String::from_utf8_unchecked(format!("{n}").bytes().rev().collect::<Vec<u8>>())
-    };
-    let reversed_n = BigUint::from_str(&reversed_n_str).expect("string built from manipulated digits should still be a valid uint");
+    let reversed_n = n.reverse_digits(10);
     let next_n = &n + &reversed_n;
 
     let check_can_index = |n: &BigUint| {
@@ -70,7 +65,7 @@ fn compute_is_lychrel_core(
         is_lychrel_cache[next_n_index].expect("just checked is_some")
     } else if depth >= (50 - 1) {
         true
-    } else if is_palindrome(&format!("{next_n}")) {
+    } else if next_n.is_palindrome_radix(10) {
         false
     } else {
         compute_is_lychrel_core(next_n, is_lychrel_cache, depth + 1, big_limit)
@@ -4,6 +4,8 @@ use std::cmp::max;
 
 use num_bigint::BigUint;
 
+use crate::euler_tools::DigitIterator;
+
 pub fn make() -> crate::Problem {
     crate::Problem {
         title: "Powerful Digit Sum",
@@ -25,13 +27,7 @@ fn core_solve() -> i64 {
 }
 
 fn big_digit_sum(a: &BigUint, b: u32) -> i64 {
-    let big_num = a.pow(b);
-    big_num
-        .to_str_radix(10)
-        .as_bytes()
-        .iter()
-        .map(|byte| (byte - b'0') as i64)
-        .sum()
+    DigitIterator::<BigUint>::new(a.pow(b)).map(i64::from).sum()
 }
 
 #[cfg(test)]
@@ -1,7 +1,8 @@
 // https://projecteuler.net/problem=57
 
 use num_bigint::BigUint;
-use num_traits::One;
+
+use crate::euler_tools::continued_fraction::{convergents, sqrt_expansion};
 
 pub fn make() -> crate::Problem {
     crate::Problem {
@@ -12,33 +13,14 @@ pub fn make() -> crate::Problem {
 }
 
 fn core_solve(max_expansion: usize) -> i64 {
-    let mut numerator: BigUint = BigUint::one();
-    let mut denominator: BigUint = BigUint::one();
-
-    let mut big_numerators = 0;
-
-    for _ in 1..=max_expansion {
-        // a_n     / b_n     =                 1 + 1 / (2 + 1 / (2 + 1 / (2 + ...)))
-        // a_{n+1} / b_{n+1} =  1 + 1 / (      2 + 1 / (2 + 1 / (2 + 1 / (2 + ...))))
-        // a_{n+1} / b_{n+1} =  1 + 1 / ( 1 +  1 + 1 / (2 + 1 / (2 + 1 / (2 + ...))))
-        // a_{n+1} / b_{n+1} =  1 + 1 / ( 1 + (1 + 1 / (2 + 1 / (2 + 1 / (2 + ...)))))
-        // a_{n+1} / b_{n+1} =  1 + 1 / ( 1 +  a_n / b_n)
-        // a_{n+1} / b_{n+1} =  1 + b_n / ( b_n + a_n )
-        // a_{n+1} / b_{n+1} =  (a_n + b_n) / (a_n + b_n) + b_n / ( a_n + b_n )
-        // a_{n+1} / b_{n+1} =  ((a_n + b_n) + b_n) / ( a_n + b_n )
-        // a_{n+1} / b_{n+1} =  (a_n + 2 * b_n) / ( a_n + b_n )
-        let new_denominator = &numerator + &denominator;
-        let new_numerator = &new_denominator + &denominator;
-
-        if new_numerator.to_string().len() > new_denominator.to_string().len() {
-            big_numerators += 1;
-        }
-
-        numerator = new_numerator;
-        denominator = new_denominator;
-    }
-
-    big_numerators
+    // the first expansion 1 + 1/2 is the second convergent of sqrt(2) = [1; 2, 2, 2, ...]
+    convergents::<BigUint, _>(sqrt_expansion(2).terms())
+        .skip(1)
+        .take(max_expansion)
+        .filter(|convergent| {
+            convergent.numerator().to_string().len() > convergent.denominator().to_string().len()
+        })
+        .count() as i64
 }
 
 #[cfg(test)]
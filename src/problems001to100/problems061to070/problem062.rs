@@ -0,0 +1,42 @@
+// https://projecteuler.net/problem=62
+
+use crate::euler_tools::digit_signature::digit_permutation_groups;
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Cubic Permutations",
+        number: 62,
+        solve: || core_solve(5),
+    }
+}
+
+fn core_solve(permutations: usize) -> i64 {
+    // permutations have the same number of digits, so each length's cubes can be grouped on
+    // their own, and a group is only complete once every cube of that length has been seen
+    let mut n = 1u64;
+    for digits in 1.. {
+        let limit = 10u64.pow(digits);
+        let mut cubes = Vec::new();
+        while n.pow(3) < limit {
+            cubes.push(n.pow(3));
+            n += 1;
+        }
+        if let Some(group) = digit_permutation_groups(cubes, permutations).first() {
+            return group[0] as i64;
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(3), 41063625)
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 127035954683)
+    }
+}
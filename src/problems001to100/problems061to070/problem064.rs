@@ -0,0 +1,30 @@
+// https://projecteuler.net/problem=64
+
+use crate::euler_tools::continued_fraction::sqrt_expansion;
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Odd Period Square Roots",
+        number: 64,
+        solve: || core_solve(10_000),
+    }
+}
+
+fn core_solve(max_n: u64) -> i64 {
+    (2..=max_n)
+        .filter(|n| sqrt_expansion(*n).period.len() % 2 == 1)
+        .count() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(13), 4);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 1322);
+    }
+}
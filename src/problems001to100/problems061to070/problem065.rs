@@ -0,0 +1,41 @@
+// https://projecteuler.net/problem=65
+
+use num_bigint::BigUint;
+
+use crate::euler_tools::{
+    continued_fraction::{convergents, e_terms},
+    ratio::Ratio,
+};
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Convergents of e",
+        number: 65,
+        solve: || core_solve(100),
+    }
+}
+
+fn core_solve(convergent: usize) -> i64 {
+    let convergent: Ratio<BigUint> = convergents(e_terms())
+        .nth(convergent - 1)
+        .expect("e has infinitely many convergents");
+    convergent
+        .numerator()
+        .to_radix_le(10)
+        .into_iter()
+        .map(|digit| digit as i64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(10), 17);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 272);
+    }
+}
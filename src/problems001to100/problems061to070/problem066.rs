@@ -0,0 +1,31 @@
+// https://projecteuler.net/problem=66
+
+use crate::euler_tools::pell;
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Diophantine Equation",
+        number: 66,
+        solve: || core_solve(1000),
+    }
+}
+
+fn core_solve(max_d: u64) -> i64 {
+    (2..=max_d)
+        .filter_map(|d| Some((pell::fundamental_solution(d)?.0, d)))
+        .max()
+        .map_or(0, |(_, d)| d as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(7), 5);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 661);
+    }
+}
@@ -1,31 +1,45 @@
-//mod problem071;
-//mod problem072;
-//mod problem073;
+mod problem071;
+mod problem072;
+mod problem073;
 mod problem074;
 mod problem075;
-//mod problem076;
-//mod problem077;
-//mod problem078;
+mod problem076;
+mod problem077;
+mod problem078;
 //mod problem079;
-//mod problem080;
+mod problem080;
 
 pub fn make_range() -> crate::ProblemList {
-    crate::ProblemList {
-        problem_range: make_problem_list(),
-    }
+    crate::ProblemList::from_problem_set(make_problem_list())
 }
 
-fn make_problem_list() -> Vec<Option<crate::Problem>> {
+fn make_problem_list() -> Vec<crate::Problem> {
     vec![
-        //Some(problem071::make()),
-        //Some(problem072::make()),
-        //Some(problem073::make()),
-        Some(problem074::make()),
-        Some(problem075::make()),
-        //Some(problem076::make()),
-        //Some(problem077::make()),
-        //Some(problem078::make()),
-        //Some(problem079::make()),
-        //Some(problem080::make()),
+        problem071::make(),
+        problem072::make(),
+        problem073::make(),
+        problem074::make(),
+        problem075::make(),
+        problem076::make(),
+        problem077::make(),
+        problem078::make(),
+        //problem079::make(),
+        problem080::make(),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::GetProblemError;
+
+    #[test]
+    fn problems_are_in_their_own_slots() {
+        let range = super::make_range();
+        assert_eq!(range.get_problem(80).unwrap().number, 80);
+        assert_eq!(range.get_problem(71).unwrap().number, 71);
+        assert!(matches!(
+            range.get_problem(79),
+            Err(GetProblemError::ProblemNotImplemented)
+        ));
+    }
+}
@@ -0,0 +1,29 @@
+// https://projecteuler.net/problem=71
+
+use crate::euler_tools::{farey::farey_neighbours, ratio::Ratio};
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Ordered Fractions",
+        number: 71,
+        solve: || core_solve(1_000_000),
+    }
+}
+
+fn core_solve(max_denominator: u64) -> i64 {
+    let (left, _) = farey_neighbours(Ratio::new(3, 7), max_denominator);
+    *left.expect("3/7 is more than 0").numerator() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(8), 2);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 428570);
+    }
+}
@@ -0,0 +1,29 @@
+// https://projecteuler.net/problem=72
+
+use crate::euler_tools::farey::farey_length;
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Counting Fractions",
+        number: 72,
+        solve: || core_solve(1_000_000),
+    }
+}
+
+fn core_solve(max_denominator: u64) -> i64 {
+    // F_n also has 0/1 and 1/1, which aren't proper fractions
+    (farey_length(max_denominator) - 2) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(8), 21);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 303963552391);
+    }
+}
@@ -0,0 +1,42 @@
+// https://projecteuler.net/problem=73
+
+use crate::euler_tools::{farey::farey_count_in_interval, ratio::Ratio};
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Counting Fractions in a Range",
+        number: 73,
+        solve: || core_solve(12_000),
+    }
+}
+
+fn core_solve(max_denominator: u64) -> i64 {
+    let (lower, upper) = (Ratio::new(1, 3), Ratio::new(1, 2));
+    // the interval is open, so leave out 1/3 and 1/2 themselves when they were counted
+    let endpoints = [lower, upper]
+        .iter()
+        .filter(|endpoint| *endpoint.denominator() <= max_denominator)
+        .count();
+    (farey_count_in_interval(lower, upper, max_denominator) - endpoints as u128) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(8), 3);
+    }
+
+    #[test]
+    fn small_denominators() {
+        for max_denominator in 0..=4 {
+            assert_eq!(super::core_solve(max_denominator), 0);
+        }
+        assert_eq!(super::core_solve(5), 1);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 7295372);
+    }
+}
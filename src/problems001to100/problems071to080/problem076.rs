@@ -0,0 +1,29 @@
+// https://projecteuler.net/problem=76
+
+use crate::euler_tools::partitions::partition_number;
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Counting Summations",
+        number: 76,
+        solve: || core_solve(100),
+    }
+}
+
+fn core_solve(total: usize) -> i64 {
+    // p(n) counts n on its own, which needs at least two positive integers
+    i64::try_from(partition_number(total) - 1u8).expect("the answer fits in an i64")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(5), 6);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 190569291);
+    }
+}
@@ -0,0 +1,37 @@
+// https://projecteuler.net/problem=77
+
+use crate::euler_tools::{partitions::restricted_partitions, prime_finder::Primes};
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Prime Summations",
+        number: 77,
+        solve: || core_solve(5000),
+    }
+}
+
+fn core_solve(min_ways: u64) -> i64 {
+    let mut limit = 16;
+    loop {
+        let primes = Primes::find_primes(limit);
+        let parts: Vec<usize> = primes.prime_iterator().map(|p| *p as usize).collect();
+        let ways: Vec<u64> = restricted_partitions(&parts, limit);
+        if let Some(total) = ways.iter().position(|ways| *ways > min_ways) {
+            return total as i64;
+        }
+        limit *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(4), 10);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 71);
+    }
+}
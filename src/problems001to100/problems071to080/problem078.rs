@@ -0,0 +1,35 @@
+// https://projecteuler.net/problem=78
+
+use crate::euler_tools::partitions::partition_numbers_mod;
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Coin Partitions",
+        number: 78,
+        solve: || core_solve(1_000_000),
+    }
+}
+
+fn core_solve(divisor: u64) -> i64 {
+    let mut limit = 1024;
+    loop {
+        let partitions = partition_numbers_mod(limit, divisor);
+        if let Some(n) = partitions.iter().position(|p| *p == 0) {
+            return n as i64;
+        }
+        limit *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(7), 5);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 55374);
+    }
+}
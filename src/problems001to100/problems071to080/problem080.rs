@@ -0,0 +1,37 @@
+// https://projecteuler.net/problem=80
+
+use num_bigint::BigUint;
+
+use crate::euler_tools::arbitrary_precision::sqrt_digits;
+
+pub fn make() -> crate::Problem {
+    crate::Problem {
+        title: "Square Root Digital Expansion",
+        number: 80,
+        solve: || core_solve(100, 100),
+    }
+}
+
+fn core_solve(max_n: u32, digit_count: usize) -> i64 {
+    (1..=max_n)
+        .filter(|n| n.isqrt().pow(2) != *n)
+        .map(|n| {
+            sqrt_digits(&BigUint::from(n), 10)
+                .take(digit_count)
+                .sum::<u32>() as i64
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn toy_example() {
+        assert_eq!(super::core_solve(2, 100), 475);
+    }
+
+    #[test]
+    fn verify_answer() {
+        assert_eq!((super::make().solve)(), 40886);
+    }
+}